@@ -0,0 +1,202 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Lines between blank lines, `start` being the index of the first of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group<S> {
+    pub start: usize,
+    pub lines: Vec<S>,
+}
+
+/// Splits lines into groups at blank lines, one group at a time. Lines with
+/// only whitespace, like a stray `\r`, count as blank and runs of them never
+/// make an empty group.
+#[derive(Debug, Clone)]
+pub struct Groups<I> {
+    lines: std::iter::Enumerate<I>,
+}
+
+pub fn groups<I, S>(lines: I) -> Groups<I::IntoIter>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    Groups { lines: lines.into_iter().enumerate() }
+}
+
+impl<I, S> Iterator for Groups<I>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    type Item = Group<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut group: Option<Group<S>> = None;
+
+        for (idx, line) in self.lines.by_ref() {
+            if line.as_ref().trim().is_empty() {
+                if group.is_some() {
+                    break;
+                }
+                continue;
+            }
+
+            group.get_or_insert_with(|| Group { start: idx, lines: Vec::new() }).lines.push(line);
+        }
+
+        group
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalorieError {
+    /// The line, counting from 1, isn't a number of calories.
+    NotANumber { line: usize, text: String },
+}
+
+/// An elf by where it comes in the input, counting from 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elf {
+    pub index: usize,
+    pub total: u64,
+}
+
+/// The `k` elves carrying the most calories seen so far, the earlier elf
+/// winning a tie. Never holds more than `k` of them.
+#[derive(Debug, Clone)]
+pub struct TopK {
+    k: usize,
+    // min-heap so the weakest is the one to go
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+}
+
+impl TopK {
+    pub fn new(k: usize) -> Self {
+        TopK { k, heap: BinaryHeap::with_capacity(k + 1) }
+    }
+
+    pub fn push(&mut self, elf: Elf) {
+        self.heap.push(Reverse((elf.total, Reverse(elf.index))));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    /// Most calories first.
+    pub fn into_sorted_vec(self) -> Vec<Elf> {
+        // `Reverse` flips the ascending order
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(index)))| Elf { index, total })
+            .collect()
+    }
+}
+
+/// Every elf with its total, one at a time.
+pub fn elves<I, S>(lines: I) -> impl Iterator<Item = Result<Elf, CalorieError>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    groups(lines).enumerate().map(|(index, group)| {
+        let mut total = 0;
+        for (offset, line) in group.lines.iter().enumerate() {
+            let text = line.as_ref().trim();
+            total += text.parse::<u64>().map_err(|_| CalorieError::NotANumber {
+                line: group.start + offset + 1,
+                text: text.to_string(),
+            })?;
+        }
+
+        Ok(Elf { index, total })
+    })
+}
+
+/// The `k` elves carrying the most calories, most first.
+pub fn top_elves<I, S>(lines: I, k: usize) -> Result<Vec<Elf>, CalorieError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut top = TopK::new(k);
+    for elf in elves(lines) {
+        top.push(elf?);
+    }
+
+    Ok(top.into_sorted_vec())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    const SAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
+    #[test]
+    fn test_sample() {
+        let top = top_elves(SAMPLE.lines(), 3).unwrap();
+
+        assert_eq!(top, [
+            Elf { index: 3, total: 24000 },
+            Elf { index: 2, total: 11000 },
+            Elf { index: 4, total: 10000 },
+        ]);
+        assert_eq!(top.iter().map(|elf| elf.total).sum::<u64>(), 45000);
+    }
+
+    #[test_case("1\r\n2\r\n\r\n3\r\n"; "crlf")]
+    #[test_case("1\n2\n\n3\n\n\n"; "trailing newlines")]
+    #[test_case("\n\n1\n2\n\n\n\n3"; "extra blank lines")]
+    #[test_case("1\n2\n  \n3"; "whitespace only")]
+    fn test_separators(input: &str) {
+        let groups = groups(input.lines()).map(|group| group.lines.len()).collect::<Vec<_>>();
+        assert_eq!(groups, [2, 1]);
+
+        let top = top_elves(input.lines(), 5).unwrap();
+        assert_eq!(top, [Elf { index: 0, total: 3 }, Elf { index: 1, total: 3 }]);
+    }
+
+    #[test_case(0, &[])]
+    #[test_case(1, &[3])]
+    #[test_case(10, &[3, 2, 4, 0, 1])]
+    fn test_k(k: usize, expected: &[usize]) {
+        let top = top_elves(SAMPLE.lines(), k).unwrap();
+        assert_eq!(top.iter().map(|elf| elf.index).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_streams_from_reader() {
+        use std::io::BufRead;
+
+        let reader = std::io::Cursor::new(SAMPLE.replace('\n', "\r\n"));
+        let lines = reader.lines().map(Result::unwrap);
+
+        assert_eq!(top_elves(lines, 1).unwrap(), [Elf { index: 3, total: 24000 }]);
+    }
+
+    #[test_case("1\n\n2\nabc\n", 4, "abc")]
+    #[test_case("1\n-5", 2, "-5")]
+    #[test_case("\r\n1.5\r\n", 2, "1.5")]
+    fn test_not_a_number(input: &str, line: usize, text: &str) {
+        assert_eq!(
+            top_elves(input.lines(), 3),
+            Err(CalorieError::NotANumber { line, text: text.to_string() })
+        );
+    }
+}
@@ -1,14 +1,7 @@
-use std::cmp::Reverse;
-
 fn main() {
     let data = include_str!("input.txt");
-    let mut data: Vec<u64> = data
-        .split("\n\n")
-        .map(|e| e.split('\n').map(|e| e.parse::<u64>().unwrap()).sum())
-        .collect();
-
-    data.sort_unstable_by_key(|e| Reverse(*e));
+    let top = day_1::top_elves(data.lines(), 3).unwrap();
 
-    println!("pt 1 {}", data.first().unwrap());
-    println!("pt 2 {}", data.iter().take(3).sum::<u64>());
+    println!("pt 1 {}", top.first().unwrap().total);
+    println!("pt 2 {}", top.iter().map(|elf| elf.total).sum::<u64>());
 }
@@ -4,7 +4,7 @@ fn main() {
     let input = include_str!("input.txt");
     let (i, monkeys) = parser::parse_monkey_list(input).unwrap();
     let mut num_inspected = vec![0; monkeys.len()];
-    let max_level: u64 = monkeys.iter().map(|m| m.divisor).product();
+    let max_level: u64 = aoc_math::lcm_all(monkeys.iter().map(|m| m.divisor));
     for _ in 0..10000 {
         for monkey in &monkeys {
             // println!("Monkey {}:", monkey.id_num);
@@ -1,154 +1,282 @@
+/// A move, as its position in the [`Tournament`].
 #[derive(Copy, Debug, Clone, PartialEq, Eq)]
-enum Move {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+struct Move(usize);
+
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Win,
+    Loss,
+    Draw,
 }
 
-impl Move {
-    const fn beats(&self) -> Self {
-        match *self {
-            Move::Rock => Move::Scissors,
-            Move::Paper => Move::Rock,
-            Move::Scissors => Move::Paper,
+impl TryFrom<char> for Outcome {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'X' => Ok(Outcome::Loss),
+            'Y' => Ok(Outcome::Draw),
+            'Z' => Ok(Outcome::Win),
+            _ => Err(ParseError::UnknownLetter(c)),
         }
     }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParseError {
+    /// A line that isn't two letters split by a space.
+    Malformed(String),
+    /// A letter that doesn't stand for any move or outcome.
+    UnknownLetter(char),
+}
+
+/// A game where the moves are in a circle, each beating the half of the
+/// others right before it and losing to the half right after it.
+///
+/// For rock-paper-scissors-lizard-Spock that takes the order rock, Spock,
+/// paper, lizard, scissors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Tournament {
+    names: Vec<&'static str>,
+    /// The letter for each move in their column of the strategy guide.
+    theirs: Vec<char>,
+    /// The letter for each move in my column of the strategy guide.
+    mine: Vec<char>,
+}
 
-    const fn loses_to(&self) -> Self {
-        match *self {
-            Move::Rock => Move::Paper,
-            Move::Paper => Move::Scissors,
-            Move::Scissors => Move::Rock,
+impl Tournament {
+    /// Panics unless there's an odd number of moves with a letter each, as
+    /// otherwise some pair of moves would neither win nor lose.
+    fn new(names: &[&'static str], theirs: &str, mine: &str) -> Self {
+        assert!(names.len() % 2 == 1, "a tournament needs an odd number of moves");
+        assert!(
+            theirs.chars().count() == names.len() && mine.chars().count() == names.len(),
+            "every move needs a letter in both columns"
+        );
+
+        Tournament {
+            names: names.to_vec(),
+            theirs: theirs.chars().collect(),
+            mine: mine.chars().collect(),
         }
     }
 
-    fn vs(&self, other: &Self) -> Outcome {
-        if self.beats() == *other {
+    fn rock_paper_scissors() -> Self {
+        Self::new(&["rock", "paper", "scissors"], "ABC", "XYZ")
+    }
+
+    fn rock_paper_scissors_lizard_spock() -> Self {
+        Self::new(&["rock", "spock", "paper", "lizard", "scissors"], "ABCDE", "VWXYZ")
+    }
+
+    fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    fn their_move(&self, letter: char) -> Result<Move, ParseError> {
+        self.theirs.iter().position(|&c| c == letter).map(Move).ok_or(ParseError::UnknownLetter(letter))
+    }
+
+    fn my_move(&self, letter: char) -> Result<Move, ParseError> {
+        self.mine.iter().position(|&c| c == letter).map(Move).ok_or(ParseError::UnknownLetter(letter))
+    }
+
+    /// How `mine` does against `theirs`.
+    fn outcome(&self, mine: Move, theirs: Move) -> Outcome {
+        let distance = (mine.0 + self.len() - theirs.0) % self.len();
+
+        if distance == 0 {
+            Outcome::Draw
+        } else if distance <= self.len() / 2 {
             Outcome::Win
-        } else if self.loses_to() == *other {
+        } else {
             Outcome::Loss
-        }else {
-            Outcome::Draw
         }
     }
-}
 
-#[derive(Copy, Debug, Clone)]
-struct TheirMove(Move);
-
-impl From<&str> for TheirMove {
-    fn from(s: &str) -> Self {
-        Self(match s {
-            "A" => Move::Rock,
-            "B" => Move::Paper,
-            "C" => Move::Scissors,
-            _ => panic!("{}, their ruh-roh", s),
+    /// The move that gets `outcome` against `theirs`, the closest one to it
+    /// in the circle when there's a choice.
+    fn response(&self, theirs: Move, outcome: Outcome) -> Move {
+        Move(match outcome {
+            Outcome::Win => (theirs.0 + 1) % self.len(),
+            Outcome::Loss => (theirs.0 + self.len() - 1) % self.len(),
+            Outcome::Draw => theirs.0,
         })
     }
 }
 
-#[derive(Copy, Debug, Clone)]
-struct MyMove(Move);
+/// What the second column of the strategy guide means.
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+enum Interpretation {
+    /// The move I play.
+    Move,
+    /// How the round should end.
+    Outcome,
+}
 
-impl From<&str> for MyMove {
-    fn from(s: &str) -> Self {
-        Self(match s {
-            "X" => Move::Rock,
-            "Y" => Move::Paper,
-            "Z" => Move::Scissors,
-            _ => panic!("{} my ruh-roh", s),
-        })
-    }
+/// Points for the move I play plus points for how the round ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Scoring {
+    moves: Vec<u64>,
+    win: u64,
+    draw: u64,
+    loss: u64,
 }
 
-impl MyMove {
-    fn score(&self) -> u64 {
-        self.0 as u64
+impl Scoring {
+    /// The `n`th move scores `n`, starting from 1, and a win, draw or loss
+    /// scores 6, 3 or 0.
+    fn standard(tournament: &Tournament) -> Self {
+        Scoring {
+            moves: (1..=tournament.len() as u64).collect(),
+            win: 6,
+            draw: 3,
+            loss: 0,
+        }
+    }
+
+    fn score(&self, mine: Move, outcome: Outcome) -> u64 {
+        self.moves[mine.0] + match outcome {
+            Outcome::Win => self.win,
+            Outcome::Draw => self.draw,
+            Outcome::Loss => self.loss,
+        }
     }
 }
 
-impl std::ops::Add<TheirMove> for MyMove {
-    type Output = u64;
+/// A line of the strategy guide, before deciding what the second letter means.
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    theirs: char,
+    second: char,
+}
+
+impl TryFrom<&str> for Entry {
+    type Error = ParseError;
 
-    fn add(self, rhs: TheirMove) -> Self::Output {
-        let outcome = self.0.vs(&rhs.0);
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let mut letters = line.split(' ').map(|part| {
+            let mut chars = part.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => None,
+            }
+        });
 
-        self.score() + outcome.score()
+        match (letters.next(), letters.next(), letters.next()) {
+            (Some(Some(theirs)), Some(Some(second)), None) => Ok(Entry { theirs, second }),
+            _ => Err(ParseError::Malformed(line.to_string())),
+        }
     }
 }
 
-impl From<(TheirMove, Outcome)> for MyMove {
-    fn from(value: (TheirMove, Outcome)) -> Self {
-        let their_move = value.0.0;
-        
-        Self(match value.1 {
-            Outcome::Win => their_move.loses_to(),
-            Outcome::Loss => their_move.beats(),
-            Outcome::Draw => their_move,
+/// Their move and mine for an entry.
+fn round(tournament: &Tournament, entry: Entry, interpretation: Interpretation) -> Result<(Move, Move), ParseError> {
+    let theirs = tournament.their_move(entry.theirs)?;
+    let mine = match interpretation {
+        Interpretation::Move => tournament.my_move(entry.second)?,
+        Interpretation::Outcome => tournament.response(theirs, Outcome::try_from(entry.second)?),
+    };
+
+    Ok((theirs, mine))
+}
+
+/// My total score from following every line of the guide.
+fn total_score(
+    input: &str,
+    tournament: &Tournament,
+    interpretation: Interpretation,
+    scoring: &Scoring,
+) -> Result<u64, ParseError> {
+    input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (theirs, mine) = round(tournament, Entry::try_from(line)?, interpretation)?;
+            Ok(scoring.score(mine, tournament.outcome(mine, theirs)))
         })
-    }
+        .sum()
 }
 
-#[derive(Copy, Debug, Clone, PartialOrd, PartialEq, Eq)]
-enum Outcome {
-    Win = 6,
-    Loss = 0,
-    Draw = 3,
+fn main() {
+    let input = include_str!("input.txt");
+    // the same guide read as rock-paper-scissors-lizard-Spock
+    let tournament = match std::env::args().any(|arg| arg == "--spock") {
+        true => Tournament::rock_paper_scissors_lizard_spock(),
+        false => Tournament::rock_paper_scissors(),
+    };
+    let scoring = Scoring::standard(&tournament);
+
+    let output = (
+        total_score(input, &tournament, Interpretation::Move, &scoring).unwrap(),
+        total_score(input, &tournament, Interpretation::Outcome, &scoring).unwrap(),
+    );
+    println!("{output:?}");
 }
 
-impl Outcome {
-    fn score(&self) -> u64 {
-        *self as u64
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    const SAMPLE: &str = "A Y\nB X\nC Z\n";
+
+    #[test_case(Interpretation::Move, 15)]
+    #[test_case(Interpretation::Outcome, 12)]
+    fn test_sample(interpretation: Interpretation, expected: u64) {
+        let tournament = Tournament::rock_paper_scissors();
+        let scoring = Scoring::standard(&tournament);
+
+        assert_eq!(total_score(SAMPLE, &tournament, interpretation, &scoring), Ok(expected));
     }
-}
 
-impl From<&str> for Outcome {
-    fn from(s: &str) -> Self {
-        match s {
-            "X" => Outcome::Loss,
-            "Y" => Outcome::Draw,
-            "Z" => Outcome::Win,
-            _ => panic!("{} outcome ruh-roh", s),
-        }
+    #[test_case("rock", "scissors", Outcome::Win)]
+    #[test_case("rock", "lizard", Outcome::Win)]
+    #[test_case("rock", "paper", Outcome::Loss)]
+    #[test_case("spock", "scissors", Outcome::Win)]
+    #[test_case("lizard", "spock", Outcome::Win)]
+    #[test_case("lizard", "rock", Outcome::Loss)]
+    #[test_case("paper", "paper", Outcome::Draw)]
+    fn test_lizard_spock(mine: &str, theirs: &str, expected: Outcome) {
+        let tournament = Tournament::rock_paper_scissors_lizard_spock();
+        let by_name = |name| Move(tournament.names.iter().position(|&n| n == name).unwrap());
+
+        assert_eq!(tournament.outcome(by_name(mine), by_name(theirs)), expected);
     }
-}
 
-fn main() {
-    let parse_from_their_my = |a: &str, b: &str| {
-        (
-            TheirMove::from(a),
-            MyMove::from(b)
-        )
-    };
+    #[test]
+    fn test_every_pair_is_decided_once() {
+        let tournament = Tournament::rock_paper_scissors_lizard_spock();
 
-    let parse_from_their_outcome = |a: &str, b: &str| {
-        let their_move = TheirMove::from(a);
+        for theirs in (0..tournament.len()).map(Move) {
+            let wins = (0..tournament.len()).map(Move).filter(|&mine| tournament.outcome(mine, theirs) == Outcome::Win);
+            assert_eq!(wins.count(), 2, "{theirs:?}");
 
-        (their_move, MyMove::from((their_move, Outcome::from(b))))
-    };
+            for outcome in [Outcome::Win, Outcome::Loss, Outcome::Draw] {
+                let mine = tournament.response(theirs, outcome);
+                assert_eq!(tournament.outcome(mine, theirs), outcome);
+            }
+        }
+    }
 
-    let output: (u64, u64) = include_str!("input.txt")
-        .split('\n')
-        .map(|line| {
-            let mut pair = line.split(' ').take(2);
-            let pair = (pair.next().unwrap(), pair.next().unwrap());
+    #[test]
+    fn test_custom_scoring() {
+        let tournament = Tournament::rock_paper_scissors();
+        let scoring = Scoring { moves: vec![0, 0, 0], win: 1, draw: 0, loss: 0 };
 
-            (
-                parse_from_their_my(pair.0, pair.1),
-                parse_from_their_outcome(pair.0, pair.1),
-            )
-        })
-        .fold((0, 0), |acc, next| {
-            let pt1_sum = acc.0;
-            let pt1_next = next.0;
+        // only the first round is a win
+        assert_eq!(total_score(SAMPLE, &tournament, Interpretation::Move, &scoring), Ok(1));
+    }
 
-            let pt2_sum = acc.1;
-            let pt2_next = next.1;
+    #[test_case("A Q", Interpretation::Move, ParseError::UnknownLetter('Q'))]
+    #[test_case("D X", Interpretation::Move, ParseError::UnknownLetter('D'))]
+    #[test_case("A W", Interpretation::Outcome, ParseError::UnknownLetter('W'))]
+    #[test_case("AX", Interpretation::Move, ParseError::Malformed("AX".to_string()))]
+    #[test_case("A X Y", Interpretation::Move, ParseError::Malformed("A X Y".to_string()))]
+    fn test_errors(line: &str, interpretation: Interpretation, expected: ParseError) {
+        let tournament = Tournament::rock_paper_scissors();
+        let scoring = Scoring::standard(&tournament);
 
-            (
-                pt1_sum + (pt1_next.1 + pt1_next.0),
-                pt2_sum + (pt2_next.1 + pt2_next.0),
-            )
-        });
-    println!("{output:?}");
+        assert_eq!(total_score(line, &tournament, interpretation, &scoring), Err(expected));
+    }
 }
@@ -0,0 +1,35 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use itertools::Itertools;
+use std::hint::black_box;
+
+use day_3::hashset::{find_common_pt1, find_common_pt2};
+
+fn rucksacks_bench(c: &mut Criterion) {
+    let input = include_str!("../src/input.txt");
+
+    let mut group = c.benchmark_group("misplaced");
+    group.bench_function("hashset", |b| {
+        b.iter(|| {
+            black_box(input).lines().filter_map(find_common_pt1).filter_map(day_3::priority).sum::<u32>()
+        })
+    });
+    group.bench_function("item set", |b| b.iter(|| day_3::misplaced_priorities(black_box(input))));
+    group.finish();
+
+    let mut group = c.benchmark_group("badges");
+    group.bench_function("hashset", |b| {
+        b.iter(|| {
+            black_box(input)
+                .lines()
+                .tuples()
+                .filter_map(|(line_1, line_2, line_3)| find_common_pt2(line_1, line_2, line_3))
+                .filter_map(day_3::priority)
+                .sum::<u32>()
+        })
+    });
+    group.bench_function("item set", |b| b.iter(|| day_3::badge_priorities(black_box(input), 3)));
+    group.finish();
+}
+
+criterion_group!(benches, rucksacks_bench);
+criterion_main!(benches);
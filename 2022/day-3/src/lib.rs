@@ -0,0 +1,236 @@
+use std::ops::BitAnd;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RucksackError {
+    /// Something other than `a`..=`z` or `A`..=`Z` in a rucksack.
+    InvalidItem(char),
+    /// The last group only has `len` rucksacks in it.
+    IncompleteGroup { len: usize },
+    /// Groups have to have at least one rucksack.
+    EmptyGroups,
+}
+
+/// Priority of an item, `a`..=`z` are 1 to 26 and `A`..=`Z` 27 to 52.
+pub fn priority(item: char) -> Option<u32> {
+    match item {
+        'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+        _ => None,
+    }
+}
+
+/// A set of item types, the bit for each one being its priority.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub const EMPTY: ItemSet = ItemSet(0);
+    pub const ALL: ItemSet = ItemSet(((1 << 52) - 1) << 1);
+
+    pub fn insert(&mut self, item: char) -> Result<(), RucksackError> {
+        let priority = priority(item).ok_or(RucksackError::InvalidItem(item))?;
+        self.0 |= 1 << priority;
+        Ok(())
+    }
+
+    pub fn contains(&self, item: char) -> bool {
+        priority(item).is_some_and(|priority| self.0 & (1 << priority) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    /// The items in every one of `sets`, nothing if there are none.
+    pub fn intersect_all(sets: impl IntoIterator<Item = ItemSet>) -> ItemSet {
+        let mut sets = sets.into_iter();
+        let first = sets.next().unwrap_or(ItemSet::EMPTY);
+
+        sets.fold(first, ItemSet::intersection)
+    }
+
+    /// The priority of every item in the set, lowest first.
+    pub fn priorities(self) -> impl Iterator<Item = u32> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            let priority = (bits != 0).then(|| bits.trailing_zeros())?;
+            bits &= bits - 1;
+            Some(priority)
+        })
+    }
+
+    pub fn priority_sum(self) -> u32 {
+        self.priorities().sum()
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl TryFrom<&str> for ItemSet {
+    type Error = RucksackError;
+
+    fn try_from(items: &str) -> Result<Self, Self::Error> {
+        let mut set = ItemSet::EMPTY;
+        for item in items.chars() {
+            set.insert(item)?;
+        }
+
+        Ok(set)
+    }
+}
+
+/// The items in both compartments of a rucksack.
+pub fn misplaced(rucksack: &str) -> Result<ItemSet, RucksackError> {
+    // every valid item is a single byte, so halving the length can't split one
+    if let Some(item) = rucksack.chars().find(|&item| priority(item).is_none()) {
+        return Err(RucksackError::InvalidItem(item));
+    }
+
+    let (first, second) = rucksack.split_at(rucksack.len() / 2);
+
+    Ok(ItemSet::try_from(first)? & ItemSet::try_from(second)?)
+}
+
+/// The items common to every rucksack of each group of `group_size`.
+pub fn badges(input: &str, group_size: usize) -> Result<Vec<ItemSet>, RucksackError> {
+    if group_size == 0 {
+        return Err(RucksackError::EmptyGroups);
+    }
+
+    let rucksacks = input.lines().map(ItemSet::try_from).collect::<Result<Vec<_>, _>>()?;
+
+    rucksacks
+        .chunks(group_size)
+        .map(|group| match group.len() {
+            len if len < group_size => Err(RucksackError::IncompleteGroup { len }),
+            _ => Ok(ItemSet::intersect_all(group.iter().copied())),
+        })
+        .collect()
+}
+
+pub fn misplaced_priorities(input: &str) -> Result<u32, RucksackError> {
+    input.lines().map(|line| misplaced(line).map(ItemSet::priority_sum)).sum()
+}
+
+pub fn badge_priorities(input: &str, group_size: usize) -> Result<u32, RucksackError> {
+    Ok(badges(input, group_size)?.into_iter().map(ItemSet::priority_sum).sum())
+}
+
+/// The first take on it with a `HashSet` per rucksack, kept to compare with.
+pub mod hashset {
+    use std::collections::HashSet;
+
+    pub fn find_common_pt1(line: &str) -> Option<char> {
+        let midpoint = line.len() / 2;
+        let compartment_1: HashSet<char> = HashSet::from_iter(line[0..midpoint].chars());
+        let compartment_2: HashSet<char> = HashSet::from_iter(line[midpoint..].chars());
+
+        compartment_1.intersection(&compartment_2).next().cloned()
+    }
+
+    pub fn find_common_pt2(line_1: &str, line_2: &str, line_3: &str) -> Option<char> {
+        let line_1: HashSet<char> = HashSet::from_iter(line_1.chars());
+        let line_2: HashSet<char> = HashSet::from_iter(line_2.chars());
+        let line_3: HashSet<char> = HashSet::from_iter(line_3.chars());
+
+        line_1
+            .intersection(&line_2)
+            .cloned()
+            .collect::<HashSet<char>>()
+            .intersection(&line_3)
+            .next()
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use itertools::Itertools;
+    use test_case::test_case;
+
+    const SAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+
+    #[test_case('a', Some(1))]
+    #[test_case('z', Some(26))]
+    #[test_case('A', Some(27))]
+    #[test_case('Z', Some(52))]
+    #[test_case('!', None)]
+    fn test_priority(item: char, expected: Option<u32>) {
+        assert_eq!(priority(item), expected);
+    }
+
+    #[test]
+    fn test_item_set() {
+        let set = ItemSet::try_from("aAbZa").unwrap();
+
+        assert_eq!(set.len(), 4);
+        assert!(set.contains('Z') && !set.contains('z'));
+        assert_eq!(set.priorities().collect::<Vec<_>>(), [1, 2, 27, 52]);
+        assert_eq!(ItemSet::ALL.len(), 52);
+        assert_eq!(ItemSet::intersect_all([]), ItemSet::EMPTY);
+    }
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(misplaced_priorities(SAMPLE), Ok(157));
+        assert_eq!(badge_priorities(SAMPLE, 3), Ok(70));
+    }
+
+    #[test]
+    fn test_group_sizes() {
+        let first = ItemSet::try_from(SAMPLE.lines().next().unwrap()).unwrap();
+        assert_eq!(badges(SAMPLE, 1).map(|groups| groups[0]), Ok(first));
+
+        // the two groups of three have nothing in common with each other
+        assert_eq!(badges(SAMPLE, 6), Ok(vec![ItemSet::EMPTY]));
+        assert_eq!(badges(SAMPLE, 2).map(|groups| groups.len()), Ok(3));
+        assert_eq!(badges(SAMPLE, 4), Err(RucksackError::IncompleteGroup { len: 2 }));
+        assert_eq!(badges(SAMPLE, 0), Err(RucksackError::EmptyGroups));
+    }
+
+    #[test_case("abc1ab", RucksackError::InvalidItem('1'))]
+    #[test_case("ab cab", RucksackError::InvalidItem(' '))]
+    #[test_case("aéb", RucksackError::InvalidItem('é'); "multi-byte item")]
+    #[test_case("ab€", RucksackError::InvalidItem('€'); "multi-byte item at the end")]
+    fn test_invalid_items(rucksack: &str, expected: RucksackError) {
+        assert_eq!(misplaced(rucksack), Err(expected));
+    }
+
+    #[test]
+    fn test_matches_hashset() {
+        let input = include_str!("input.txt");
+        let one = |set: ItemSet| {
+            assert!(set.len() <= 1);
+            set.priorities().next()
+        };
+
+        for line in input.lines() {
+            assert_eq!(one(misplaced(line).unwrap()), hashset::find_common_pt1(line).and_then(priority));
+        }
+
+        let badges = badges(input, 3).unwrap();
+        for (badge, (line_1, line_2, line_3)) in badges.into_iter().zip(input.lines().tuples()) {
+            assert_eq!(one(badge), hashset::find_common_pt2(line_1, line_2, line_3).and_then(priority));
+        }
+    }
+}
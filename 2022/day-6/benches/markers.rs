@@ -0,0 +1,27 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+fn markers_bench(c: &mut Criterion) {
+    let input = include_bytes!("../src/input.txt");
+
+    c.bench_function("with_string_baked_in", |b| {
+        b.iter(day_6::with_string_baked_in)
+    });
+
+    c.bench_function("with_io", |b| b.iter(|| day_6::with_io().unwrap()));
+
+    c.bench_function("first_distinct_window", |b| {
+        b.iter(|| {
+            day_6::first_distinct_window::<{ day_6::MESSAGE_WIDTH }>(
+                black_box(input).iter().copied(),
+            )
+        })
+    });
+
+    c.bench_function("find_markers", |b| {
+        b.iter(|| day_6::find_markers(black_box(input).iter().copied()))
+    });
+}
+
+criterion_group!(benches, markers_bench);
+criterion_main!(benches);
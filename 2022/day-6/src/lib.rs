@@ -11,6 +11,91 @@ fn methods_yield_the_same() {
     let meth_2 = with_io().unwrap();
 
     assert_eq!(meth_1, meth_2);
+    assert_eq!(
+        find_markers(include_bytes!("input.txt").iter().copied()).start_of_message,
+        Some(meth_1)
+    );
+}
+
+/// Marker width for the start of a packet.
+pub const PACKET_WIDTH: usize = 4;
+/// Marker width for the start of a message.
+pub const MESSAGE_WIDTH: usize = 14;
+
+/// Tracks the last position each byte was seen at so that "are the last `N`
+/// symbols distinct" can be answered in O(1) per symbol, regardless of `N`.
+///
+/// An empty window has no marker to find, so `N` of 0 doesn't compile.
+#[derive(Debug, Clone)]
+pub struct DistinctWindow<const N: usize> {
+    // 1-based position of the most recent occurrence, 0 for never seen
+    last_seen: [usize; 256],
+    run_start: usize,
+    consumed: usize,
+}
+
+impl<const N: usize> Default for DistinctWindow<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> DistinctWindow<N> {
+    pub fn new() -> Self {
+        const { assert!(N > 0, "a marker needs at least one symbol") };
+
+        Self {
+            last_seen: [0; 256],
+            run_start: 0,
+            consumed: 0,
+        }
+    }
+
+    /// Feeds the next symbol, returning the number of symbols consumed so far
+    /// if the last `N` of them are all distinct.
+    pub fn push(&mut self, symbol: u8) -> Option<usize> {
+        let seen = &mut self.last_seen[symbol as usize];
+        self.run_start = self.run_start.max(*seen);
+        self.consumed += 1;
+        *seen = self.consumed;
+
+        (self.consumed - self.run_start >= N).then_some(self.consumed)
+    }
+}
+
+/// Position just past the first run of `N` distinct symbols.
+pub fn first_distinct_window<const N: usize>(
+    symbols: impl IntoIterator<Item = u8>,
+) -> Option<usize> {
+    let mut window = DistinctWindow::<N>::new();
+    symbols.into_iter().find_map(|symbol| window.push(symbol))
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Markers {
+    pub start_of_packet: Option<usize>,
+    pub start_of_message: Option<usize>,
+}
+
+/// Finds both the start-of-packet and start-of-message markers in a single
+/// pass, stopping as soon as both have been seen.
+pub fn find_markers(symbols: impl IntoIterator<Item = u8>) -> Markers {
+    let mut packet = DistinctWindow::<PACKET_WIDTH>::new();
+    let mut message = DistinctWindow::<MESSAGE_WIDTH>::new();
+    let mut markers = Markers::default();
+
+    for symbol in symbols {
+        let found_packet = packet.push(symbol);
+        let found_message = message.push(symbol);
+        markers.start_of_packet = markers.start_of_packet.or(found_packet);
+        markers.start_of_message = markers.start_of_message.or(found_message);
+
+        if markers.start_of_message.is_some() && markers.start_of_packet.is_some() {
+            break;
+        }
+    }
+
+    markers
 }
 
 pub fn with_string_baked_in() -> usize {
@@ -40,9 +125,41 @@ pub fn with_io() -> io::Result<usize> {
     let mut pre_seek_pos = f.seek(SeekFrom::Start(0))?;
 
     while !buf.iter().all_unique() {
-        f.read(&mut buf[..])?;
+        f.read_exact(&mut buf[..])?;
         pre_seek_pos = f.seek(SeekFrom::Start(pre_seek_pos + 1))?;
     }
 
     Ok((WINDOW_WIDTH + pre_seek_pos - 1) as usize)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19)]
+    #[test_case("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23)]
+    #[test_case("nppdvjthqldpwncqszvftbrmjlhg", 6, 23)]
+    #[test_case("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29)]
+    #[test_case("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26)]
+    fn finds_both_markers_in_one_pass(signal: &str, packet: usize, message: usize) {
+        let markers = find_markers(signal.bytes());
+
+        assert_eq!(markers.start_of_packet, Some(packet));
+        assert_eq!(markers.start_of_message, Some(message));
+    }
+
+    #[test_case("", None)]
+    #[test_case("abc", None)]
+    #[test_case("aaaa", None)]
+    #[test_case("abcd", Some(4))]
+    #[test_case("abcabcd", Some(7))]
+    fn window_of_four(signal: &str, expected: Option<usize>) {
+        assert_eq!(first_distinct_window::<4>(signal.bytes()), expected);
+    }
+
+    #[test]
+    fn window_of_one_is_the_first_symbol() {
+        assert_eq!(first_distinct_window::<1>(*b"zz"), Some(1));
+    }
+}
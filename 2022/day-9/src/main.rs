@@ -1,13 +1,16 @@
+use aoc_geometry::{Heading, Vec2};
 use itertools::Itertools;
 use lending_iterator::{lending_iterator::constructors::windows_mut, prelude::*};
-use std::{collections::HashSet, ops::AddAssign};
+use std::collections::HashSet;
+
+type Point = Vec2<i64>;
 
 fn main() {
     let input = include_str!("input.txt");
     
     let grid = input
         .lines()
-        .map(Move::from)
+        .map(Motion::from)
         .fold(Grid::new(), |mut grid, next_move| {
             grid.step(next_move);
             grid
@@ -28,36 +31,25 @@ impl Grid {
         Self {
             // i don't want to do the math to calculate this from the input
             dim: (121, 121),
-            knot_pos: vec![Point(0, 0); 10],
-            trail: HashSet::from([Point(0, 0)]),
+            knot_pos: vec![Point::ZERO; 10],
+            trail: HashSet::from([Point::ZERO]),
         }
     }
 
-    pub fn step(&mut self, step_move: Move) {
-        use Move::*;
-        for step in step_move.iter() {
-            self.knot_pos[0] += &step;
+    pub fn step(&mut self, motion: Motion) {
+        for _ in 0..motion.steps {
+            self.knot_pos[0] += motion.heading.unit();
 
             let mut i = 0;
             let last_idx = self.knot_pos.len() - 2;
             let mut iter = self.knot_pos.windows_mut::<2>();
 
             while let Some([ref mut k_1, ref mut k_2]) = iter.next() {
-                if k_2.distance_to(&k_1) > std::f64::consts::SQRT_2 {
-                    // there's no else case! if distance is 0 in this direction
-                    // then no move
-                    
-                    if k_1.0 > k_2.0 {
-                        *k_2 += &Right(1)
-                    } else if k_1.0 < k_2.0 {
-                        *k_2 += &Left(1)
-                    }
+                if k_2.chebyshev(*k_1) > 1 {
+                    // a single step, diagonal if they're in neither the same
+                    // row nor column
+                    *k_2 += (*k_1 - *k_2).signum();
 
-                    if k_1.1 > k_2.1 {
-                        *k_2 += &Up(1);
-                    } else if k_1.1 < k_2.1 {
-                        *k_2 += &Down(1);
-                    }
                     if i == last_idx {
                         self.trail.insert(*k_2);
                     }
@@ -90,12 +82,12 @@ impl std::fmt::Display for Grid {
                 std::borrow::Cow::Owned(i.to_string())
             };
 
-            grid[(OFFSET_Y as i64 - knot.1) as usize][(OFFSET_X as i64 + knot.0) as usize] =
+            grid[(OFFSET_Y as i64 + knot.y) as usize][(OFFSET_X as i64 + knot.x) as usize] =
                 stamp;
         }
 
         for trail_pt in self.trail.iter() {
-            let current = &mut grid[(OFFSET_Y as i64 - trail_pt.1) as usize][(OFFSET_X as i64 + trail_pt.0) as usize];
+            let current = &mut grid[(OFFSET_Y as i64 + trail_pt.y) as usize][(OFFSET_X as i64 + trail_pt.x) as usize];
             if !current.chars().any(|c| c.is_alphanumeric()) {
                 *current = std::borrow::Cow::Borrowed("#");
             }
@@ -105,83 +97,39 @@ impl std::fmt::Display for Grid {
         write!(f, "\n{}", grid)
     }
 }
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-struct Point(i64, i64);
-
-impl Point {
-    fn distance_to(&self, other: &Self) -> f64 {
-        f64::sqrt((self.0 - other.0).pow(2) as f64 + (self.1 - other.1).pow(2) as f64)
-    }
-}
-
-impl AddAssign<&Move> for Point {
-    fn add_assign(&mut self, rhs: &Move) {
-        use Move::*;
-
-        let next_pos = match rhs {
-            Up(val) => Point(self.0, self.1.saturating_add(*val as i64)),
-            Down(val) => Point(self.0, self.1.saturating_sub(*val as i64)),
-            Left(val) => Point(self.0.saturating_sub(*val as i64), self.1),
-            Right(val) => Point(self.0.saturating_add(*val as i64), self.1),
-        };
-
-        self.0 = next_pos.0;
-        self.1 = next_pos.1;
-    }
+/// A line of the input, going `steps` steps towards `heading`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Motion {
+    heading: Heading,
+    steps: usize,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-enum Move {
-    Up(usize),
-    Down(usize),
-    Left(usize),
-    Right(usize),
-}
-
-impl Move {
-    fn iter(&self) -> impl Iterator<Item = Move> + '_ {
-        use Move::*;
-
-        let moves = match self {
-            Up(d) | Down(d) | Left(d) | Right(d) => d.clone(),
-        };
-
-        (0..moves).into_iter().map(move |_| match self {
-            Up(_) => Up(1),
-            Down(_) => Down(1),
-            Left(_) => Left(1),
-            Right(_) => Right(1),
-        })
-    }
-}
-
-impl From<&str> for Move {
+impl From<&str> for Motion {
     fn from(cmd: &str) -> Self {
         let (dir, val): (&str, &str) = cmd.split(' ').take(2).collect_tuple().unwrap();
-        let val: usize = val.parse().unwrap();
-
-        match dir {
-            "U" => Self::Up(val),
-            "D" => Self::Down(val),
-            "L" => Self::Left(val),
-            "R" => Self::Right(val),
-            _ => panic!("unexpected move direction {}", dir),
+        let steps: usize = val.parse().unwrap();
+
+        let heading = dir.chars().exactly_one().ok().and_then(Heading::from_letter);
+        match heading {
+            Some(heading) => Self { heading, steps },
+            None => panic!("unexpected move direction {}", dir),
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{Grid, Move, Point};
+    use crate::{Grid, Motion, Point};
+    use aoc_geometry::Heading;
     use test_case::test_case;
 
-    #[test_case("U 1", Move::Up(1))]
-    #[test_case("D 5", Move::Down(5))]
-    #[test_case("L 2", Move::Left(2))]
-    #[test_case("R 9", Move::Right(9))]
-    fn from_str_to_move(input: &str, expected: Move) {
-        let actual = Move::from(input);
-        assert_eq!(expected, actual);
+    #[test_case("U 1", Heading::North, 1)]
+    #[test_case("D 5", Heading::South, 5)]
+    #[test_case("L 2", Heading::West, 2)]
+    #[test_case("R 9", Heading::East, 9)]
+    fn from_str_to_move(input: &str, heading: Heading, steps: usize) {
+        let actual = Motion::from(input);
+        assert_eq!(Motion { heading, steps }, actual);
     }
 
     #[test]
@@ -197,7 +145,7 @@ U 20";
 
         let grid = input
             .lines()
-            .map(Move::from)
+            .map(Motion::from)
             .inspect(|m| println!("== {m:?} =="))
             .fold(Grid::new(), |mut grid, next_move| {
                 grid.step(next_move);
@@ -209,14 +157,11 @@ U 20";
 
     #[test]
     fn test_distance_fn_lateral() {
-        assert_eq!(1., Point(0, 0).distance_to(&Point(0, 1)))
+        assert_eq!(1, Point::new(0, 0).chebyshev(Point::new(0, 1)))
     }
 
     #[test]
     fn test_distance_fn_diag() {
-        assert_eq!(
-            std::f64::consts::SQRT_2,
-            Point(0, 0).distance_to(&Point(1, 1))
-        )
+        assert_eq!(1, Point::new(0, 0).chebyshev(Point::new(1, 1)))
     }
 }
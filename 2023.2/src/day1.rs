@@ -0,0 +1,206 @@
+use std::collections::VecDeque;
+
+/// The words that read as digits, e.g. `"7"` or `"seven"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+}
+
+impl Vocabulary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Just `0` through `9`.
+    pub fn digits() -> Self {
+        (0..10).fold(Self::new(), |vocabulary, digit| vocabulary.with(&digit.to_string(), digit))
+    }
+
+    /// `0` through `9` along with `one` through `nine` spelled out.
+    pub fn spelled() -> Self {
+        ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"]
+            .into_iter()
+            .zip(1..)
+            .fold(Self::digits(), |vocabulary, (word, digit)| vocabulary.with(word, digit))
+    }
+
+    pub fn with(mut self, word: &str, digit: u32) -> Self {
+        assert!(!word.is_empty(), "digit words can't be empty");
+        self.words.push((word.to_string(), digit));
+        self
+    }
+
+    pub fn scanner(&self) -> Scanner {
+        Scanner::new(self)
+    }
+}
+
+/// A digit found in a line, `position` being the byte offset it starts at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Found {
+    pub digit: u32,
+    pub position: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calibration {
+    pub first: Found,
+    pub last: Found,
+}
+
+impl Calibration {
+    pub fn value(&self) -> u32 {
+        self.first.digit * 10 + self.last.digit
+    }
+}
+
+/// An Aho-Corasick automaton over a [`Vocabulary`], so every word in a line
+/// is found in a single pass, overlapping ones like `eightwo` included.
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    /// The next state for every state and byte, failure links already
+    /// followed.
+    transitions: Vec<[usize; 256]>,
+    /// `(digit, word length)` of every word ending in each state.
+    outputs: Vec<Vec<(u32, usize)>>,
+}
+
+impl Scanner {
+    fn new(vocabulary: &Vocabulary) -> Self {
+        // the trie, state 0 being the root
+        let mut children = vec![[None::<usize>; 256]];
+        let mut outputs = vec![Vec::new()];
+        for (word, digit) in &vocabulary.words {
+            let mut state = 0;
+            for &byte in word.as_bytes() {
+                state = match children[state][byte as usize] {
+                    Some(next) => next,
+                    None => {
+                        children.push([None; 256]);
+                        outputs.push(Vec::new());
+                        children[state][byte as usize] = Some(children.len() - 1);
+                        children.len() - 1
+                    }
+                };
+            }
+            outputs[state].push((*digit, word.len()));
+        }
+
+        // breadth first, so a state's failure link is done before its children
+        let mut transitions = vec![[0; 256]; children.len()];
+        let mut failure = vec![0; children.len()];
+        let mut queue = VecDeque::from([0]);
+        while let Some(state) = queue.pop_front() {
+            for byte in 0..256 {
+                match children[state][byte] {
+                    Some(child) => {
+                        failure[child] = if state == 0 { 0 } else { transitions[failure[state]][byte] };
+                        let inherited = outputs[failure[child]].clone();
+                        outputs[child].extend(inherited);
+                        transitions[state][byte] = child;
+                        queue.push_back(child);
+                    }
+                    None => transitions[state][byte] = transitions[failure[state]][byte],
+                }
+            }
+        }
+
+        Scanner { transitions, outputs }
+    }
+
+    /// The first and last digit in `line`, by where they start.
+    pub fn scan(&self, line: &str) -> Option<Calibration> {
+        let mut state = 0;
+        let mut found: Option<Calibration> = None;
+
+        for (idx, &byte) in line.as_bytes().iter().enumerate() {
+            state = self.transitions[state][byte as usize];
+
+            for &(digit, len) in &self.outputs[state] {
+                let next = Found { digit, position: idx + 1 - len };
+                match found.as_mut() {
+                    None => found = Some(Calibration { first: next, last: next }),
+                    Some(calibration) => {
+                        if next.position < calibration.first.position {
+                            calibration.first = next;
+                        }
+                        if next.position > calibration.last.position {
+                            calibration.last = next;
+                        }
+                    }
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Sum of the calibration values of every line, lines without any digit
+    /// count as zero.
+    pub fn calibration_sum(&self, input: &str) -> u32 {
+        input.lines().filter_map(|line| self.scan(line)).map(|calibration| calibration.value()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("two1nine", 29)]
+    #[test_case("eightwothree", 83)]
+    #[test_case("abcone2threexyz", 13)]
+    #[test_case("xtwone3four", 24)]
+    #[test_case("4nineeightseven2", 42)]
+    #[test_case("zoneight234", 14)]
+    #[test_case("7pqrstsixteen", 76)]
+    #[test_case("oneight", 18)]
+    #[test_case("twone", 21)]
+    #[test_case("sevenine", 79)]
+    #[test_case("eighthree", 83)]
+    #[test_case("treb7uchet", 77)]
+    fn test_spelled(line: &str, expected: u32) {
+        let scanner = Vocabulary::spelled().scanner();
+        assert_eq!(scanner.scan(line).map(|calibration| calibration.value()), Some(expected));
+    }
+
+    #[test]
+    fn test_positions() {
+        let scanner = Vocabulary::spelled().scanner();
+        assert_eq!(
+            scanner.scan("xtwone3four"),
+            Some(Calibration {
+                first: Found { digit: 2, position: 1 },
+                last: Found { digit: 4, position: 7 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_digits_only() {
+        let scanner = Vocabulary::digits().scanner();
+        assert_eq!(scanner.scan("one2three4five").map(|calibration| calibration.value()), Some(24));
+        assert_eq!(scanner.scan("onetwo"), None);
+    }
+
+    #[test]
+    fn test_nested_words() {
+        // "seventeen" contains "seven" and ends after "teen" starts
+        let scanner = Vocabulary::new().with("seventeen", 1).with("seven", 7).with("teen", 3).scanner();
+        let calibration = scanner.scan("seventeen").unwrap();
+
+        assert_eq!(calibration.first.position, 0);
+        assert_eq!(calibration.last, Found { digit: 3, position: 5 });
+    }
+
+    #[test]
+    fn test_calibration_sum() {
+        let input = r#"1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+"#;
+        assert_eq!(Vocabulary::digits().scanner().calibration_sum(input), 142);
+        assert_eq!(Vocabulary::digits().scanner().calibration_sum("nothing here"), 0);
+    }
+}
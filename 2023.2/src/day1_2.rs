@@ -1,10 +1,70 @@
-fn find_sum(input: &str) -> u32 {
-    input.lines().flat_map(parser::parse_line).fold(0, |acc, (_, val)| acc + val)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::day1::Vocabulary;
+    use aoc_2023_strategies::calibration_line;
+    use proptest::prelude::*;
+
+    const SAMPLE: &str = r#"two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+"#;
+
+    fn find_sum(input: &str) -> u32 {
+        Vocabulary::spelled().scanner().calibration_sum(input)
+    }
+
+    fn reference_sum(input: &str) -> u32 {
+        input.lines().flat_map(parser::parse_line).fold(0, |acc, (_, val)| acc + val)
+    }
+
+    #[test]
+    fn test_matches_reference() {
+        let input = include_str!("../data/day1.txt");
+        assert_eq!(find_sum(SAMPLE), reference_sum(SAMPLE));
+        assert_eq!(find_sum(input), reference_sum(input));
+    }
+
+    /// Whether the old parser gets through `line`. After a digit word it
+    /// resumes wherever the rest of the line first turns up, which can be
+    /// inside the word it just read, as in `twowo`, and from there it may go
+    /// round in circles forever.
+    fn parser_resumes_correctly(line: &str) -> bool {
+        const WORDS: [&str; 10] =
+            ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+        (0..line.len()).all(|start| {
+            let input = &line[start..];
+            WORDS.iter().filter(|word| input.starts_with(*word)).all(|word| {
+                let rem = &input[word.len()..];
+                rem.is_empty() || input.find(rem) == Some(word.len())
+            })
+        })
+    }
+
+    #[test]
+    fn test_parser_known_difference() {
+        // the old parser never finishes these
+        assert!(!parser_resumes_correctly("twowo"));
+        assert!(!parser_resumes_correctly("1onen"));
+        assert_eq!(find_sum("twowo\n1onen"), 22 + 11);
+
+        assert!(parser_resumes_correctly("eightwothree"));
+    }
+
+    proptest! {
+        #[test]
+        fn test_scanner_matches_reference(line in calibration_line()) {
+            prop_assume!(parser_resumes_correctly(&line));
+            let scanner = Vocabulary::spelled().with("zero", 0).scanner();
+            let (_, expected) = parser::parse_line(&line).unwrap();
+            prop_assert_eq!(scanner.scan(&line).map(|calibration| calibration.value()), Some(expected));
+        }
+    }
 
     #[test]
     fn test_find_sum() {
@@ -20,6 +80,8 @@ zoneight234
     }
 }
 
+/// The original line parser, kept to check the scanner against.
+#[cfg(test)]
 mod parser {
     use nom::{
         branch::alt, bytes::complete::tag_no_case, character::complete::digit1, combinator::{map, value}, error::{Error, ErrorKind}, FindSubstring, IResult
@@ -1,2 +1,3 @@
+pub mod day1;
 mod day1_1;
-mod day1_2;
\ No newline at end of file
+mod day1_2;
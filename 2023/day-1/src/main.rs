@@ -1,6 +1,8 @@
+use aoc_2023::day1::Vocabulary;
+#[cfg(test)]
 use itertools::{self, Itertools};
+#[cfg(test)]
 use nom::{
-    
     branch::alt,
     bytes::complete::{tag, take},
     character::complete::one_of,
@@ -11,10 +13,11 @@ use nom::{
 
 fn main() {
     let input = include_str!("input.txt");
-    let result = parse_file_2(input);
+    let result = Vocabulary::spelled().scanner().calibration_sum(input);
     println!("result: {}", result);
 }
 
+#[cfg(test)]
 fn parse_line(input: &str) -> i32 {
     // find first digit in input reading from left to right
     let first_digit = input.find(char::is_numeric).unwrap();
@@ -30,6 +33,9 @@ fn parse_line(input: &str) -> i32 {
     .unwrap()
 }
 
+// the original nom parser, kept to check the scanner against
+
+#[cfg(test)]
 fn parse_file_2(input: &str) -> u32 {
     input
         .lines()
@@ -38,6 +44,7 @@ fn parse_file_2(input: &str) -> u32 {
         .sum::<u32>()
 }
 
+#[cfg(test)]
 fn parse_line_2(input: &str) -> IResult<&str, u32> {
     combinator::map(
         combinator::map(
@@ -59,14 +66,15 @@ fn parse_line_2(input: &str) -> IResult<&str, u32> {
     )(input)
 }
 
+#[cfg(test)]
 fn parse_number(input: &str) -> IResult<&str, u32> {
-    dbg!(input);
     alt((
         parse_str_digit,
         combinator::map(one_of("0123456789"), |d| d.to_string().parse().unwrap()),
     ))(input)
 }
 
+#[cfg(test)]
 fn parse_str_digit(input: &str) -> IResult<&str, u32> {
     let num_names = [
         ("zero", 0),
@@ -94,6 +102,8 @@ fn parse_str_digit(input: &str) -> IResult<&str, u32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_2023_strategies::calibration_line;
+    use proptest::prelude::*;
     use test_case::test_case;
 
     #[test_case("7three3zmmfvxtsdsthree", 73)]
@@ -185,4 +195,26 @@ zoneight234
         assert_eq!(parse_line_2("69").unwrap().1, 69);
         assert_eq!(parse_file_2(input), 281);
     }
+
+    #[test]
+    fn test_scanner_matches_parser() {
+        let input = include_str!("input.txt");
+        let scanner = Vocabulary::spelled().scanner();
+
+        assert_eq!(scanner.calibration_sum(input), parse_file_2(input));
+        for line in input.lines() {
+            let (_, expected) = parse_line_2(line).unwrap();
+            assert_eq!(scanner.scan(line).map(|calibration| calibration.value()), Some(expected), "{line}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_scanner_matches_parse_line_2(line in calibration_line()) {
+            // the parser reads "zero" too
+            let scanner = Vocabulary::spelled().with("zero", 0).scanner();
+            let (_, expected) = parse_line_2(&line).unwrap();
+            prop_assert_eq!(scanner.scan(&line).map(|calibration| calibration.value()), Some(expected));
+        }
+    }
 }
@@ -1,103 +1,148 @@
-use std::str::FromStr;
+use std::collections::BTreeMap;
 
-use nom::{IResult, bytes::complete::tag_no_case, character::complete::{u32, space1, line_ending}, sequence::{preceded, tuple, terminated}, branch::alt, multi::separated_list1};
-use strum::{Display, EnumString};
+use nom::{IResult, bytes::complete::tag_no_case, character::complete::{u32, alpha1, space1}, sequence::{preceded, tuple, terminated}, multi::separated_list1, combinator::{all_consuming, map_opt}};
 
 fn main() {
-    let reference_pull = Pull::new(Some(Red(12)), Some(Blue(14)), Some(Green(13)));
+    let palette = Palette::standard();
+    let bag = CubeSet::new().with("red", 12).with("green", 13).with("blue", 14);
 
-    let (_, games) = parse_games(include_str!("input.txt")).unwrap();
+    let games = parse_games(include_str!("input.txt"), &palette).unwrap();
 
     let sum_of_ids = games.iter()
-            .filter(|g| g.pulls.iter().all(|p| p.cmp(&reference_pull) != std::cmp::Ordering::Greater))
+            .filter(|g| g.is_possible(&bag))
             .map(|g| g.id)
-            // .collect::<Vec<u32>>();
             .sum::<u32>();
     println!("{sum_of_ids:?}");
 
     let power = games.iter()
-        .map(|g| g.pulls.iter()
-            .fold(Pull::default(), |max, next| Pull::new(
-                Some(Red(max.red.max(next.red))),
-                Some(Blue(max.blue.max(next.blue))),
-                Some(Green(max.green.max(next.green))),
-            ))
-        )
-        .map(|p| p.red * p.blue * p.green)
-        .sum::<u32>();
+        .map(|g| g.fewest_cubes(&palette).power())
+        .sum::<u64>();
 
     println!("{power}");
 }
 
 impl Game{
     #[must_use]
-    pub fn new(id: u32, pulls: Vec<Pull>) -> Self {
+    pub fn new(id: u32, pulls: Vec<CubeSet>) -> Self {
         Game {
             id,
             pulls,
         }
     }
+
+    /// Whether every pull could have come out of `bag`.
+    pub fn is_possible(&self, bag: &CubeSet) -> bool {
+        self.pulls.iter().all(|pull| pull.fits_within(bag))
+    }
+
+    /// The smallest bag every pull fits within, with a count for every colour
+    /// of the palette even if it never came up.
+    pub fn fewest_cubes(&self, palette: &Palette) -> CubeSet {
+        self.pulls.iter().fold(palette.empty_set(), |fewest, pull| fewest.union(pull))
+    }
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd)]
-struct Pull {
-    red: u32,
-    blue: u32,
-    green: u32,
+/// Number of cubes of each colour, colours not in the set count as zero.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct CubeSet {
+    counts: BTreeMap<String, u32>,
 }
 
-impl Pull {
+impl CubeSet {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with(mut self, colour: &str, count: u32) -> Self {
+        self.counts.insert(colour.to_string(), count);
+        self
+    }
+
+    pub fn count(&self, colour: &str) -> u32 {
+        self.counts.get(colour).copied().unwrap_or_default()
+    }
+
+    pub fn colours(&self) -> impl Iterator<Item = &str> {
+        self.counts.keys().map(String::as_str)
+    }
+
+    /// Whether there's at least as many cubes of every colour in `bag`.
+    pub fn fits_within(&self, bag: &CubeSet) -> bool {
+        self.counts.iter().all(|(colour, count)| *count <= bag.count(colour))
+    }
+
+    /// The componentwise maximum, the smallest set both fit within.
     #[must_use]
-    pub fn new(red: Option<Red>, blue: Option<Blue>, green: Option<Green>) -> Self {
-        Self {
-            red: red.map(|r| r.0).unwrap_or_default(),
-            blue: blue.map(|b| b.0).unwrap_or_default(),
-            green: green.map(|g| g.0).unwrap_or_default(),
+    pub fn union(&self, other: &CubeSet) -> CubeSet {
+        let mut union = self.clone();
+        for (colour, count) in &other.counts {
+            let max = union.counts.entry(colour.clone()).or_default();
+            *max = (*max).max(*count);
         }
+
+        union
+    }
+
+    /// Product of the counts of every colour in the set.
+    pub fn power(&self) -> u64 {
+        self.counts.values().map(|count| u64::from(*count)).product()
     }
 }
 
-impl Ord for Pull {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self.red == other.red && self.blue == other.blue && self.green == other.green {
-            std::cmp::Ordering::Equal
-        }
-        else if self.red <= other.red && self.blue <= other.blue && self.green <= other.green {
-            std::cmp::Ordering::Less
-        }
-        else {
-            std::cmp::Ordering::Greater
-        }
+/// The cube colours a game may mention.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colours: Vec<String>,
+}
+
+impl Palette {
+    pub fn new<'a>(colours: impl IntoIterator<Item = &'a str>) -> Self {
+        Palette { colours: colours.into_iter().map(str::to_lowercase).collect() }
+    }
+
+    pub fn standard() -> Self {
+        Self::new(["red", "green", "blue"])
+    }
+
+    pub fn contains(&self, colour: &str) -> bool {
+        self.colours.iter().any(|known| known == colour)
+    }
+
+    /// None of each colour.
+    pub fn empty_set(&self) -> CubeSet {
+        self.colours.iter().fold(CubeSet::new(), |set, colour| set.with(colour, 0))
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    /// The line, counting from 1, isn't a game.
+    Malformed { line: usize },
+    /// A pull in game `game` has cubes of a colour the palette doesn't know.
+    UnknownColour { game: u32, colour: String },
+}
 
 pub fn parse_game_id(i: &str) -> IResult<&str, u32> {
     preceded(tag_no_case("game "), u32)(i)
 }
 
-fn parse_color(i: &str) -> IResult<&str, Color> {
-    nom::combinator::map(alt((tag_no_case("red"), tag_no_case("green"), tag_no_case("blue"))), |tag: &str| {
-        Color::from_str(tag).unwrap()
-    })(i)
+fn parse_color(i: &str) -> IResult<&str, String> {
+    nom::combinator::map(alpha1, str::to_lowercase)(i)
 }
 
-fn parse_pull_part(i: &str) -> IResult<&str, (u32, Color)> {
+fn parse_pull_part(i: &str) -> IResult<&str, (u32, String)> {
     tuple((terminated(u32, space1), parse_color))(i)
 }
 
-fn parse_full_pull(i: &str) -> IResult<&str, Pull> {
-    nom::combinator::map(separated_list1(tag_no_case(", "), parse_pull_part), |pulls| {
-        pulls.into_iter()
-            .fold(Pull::default(), |mut pull, (count, color)| {
-                match color {
-                    Color::Red => pull.red = count,
-                    Color::Blue => pull.blue = count,
-                    Color::Green => pull.green = count,
-                }
-
-                pull
-            })
+/// A single pull, which can only name each colour once.
+fn parse_full_pull(i: &str) -> IResult<&str, CubeSet> {
+    map_opt(separated_list1(tag_no_case(", "), parse_pull_part), |pulls| {
+        pulls.into_iter().try_fold(CubeSet::new(), |pull, (count, color)| {
+            let repeated = pull.colours().any(|seen| seen == color);
+            (!repeated).then(|| pull.with(&color, count))
+        })
     })(i)
 }
 
@@ -113,30 +158,30 @@ fn parse_game(i: &str) -> IResult<&str, Game> {
     )(i)
 }
 
-fn parse_games(i: &str) -> IResult<&str, Vec<Game>> {
-    separated_list1(line_ending, parse_game)(i)
+/// Every game in `i`, one per line, which may only have cubes of colours in
+/// `palette`.
+fn parse_games(i: &str, palette: &Palette) -> Result<Vec<Game>, GameError> {
+    i.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(idx, line)| {
+            let (_, game) = all_consuming(parse_game)(line)
+                .map_err(|_| GameError::Malformed { line: idx + 1 })?;
+
+            let unknown = game.pulls.iter().flat_map(CubeSet::colours).find(|colour| !palette.contains(colour));
+            match unknown {
+                Some(colour) => Err(GameError::UnknownColour { game: game.id, colour: colour.to_string() }),
+                None => Ok(game),
+            }
+        })
+        .collect()
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Display, EnumString)]
-#[strum(ascii_case_insensitive)]
-#[strum(serialize_all = "lowercase")]
-enum Color {
-    Red,
-    Green,
-    Blue,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Red(u32);
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Green(u32);
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Blue(u32);
-
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 struct Game {
     id: u32,
-    pulls: Vec<Pull>
+    pulls: Vec<CubeSet>
 }
 
 #[cfg(test)]
@@ -146,12 +191,13 @@ mod test {
     use test_case::test_case;
 
 
-    fn color_enum_strateg() -> impl Strategy<Value = Color> {
-        prop_oneof![
-            Just(Color::Red),
-            Just(Color::Green),
-            Just(Color::Blue)
-        ]
+    fn color_strategy() -> impl Strategy<Value = String> {
+        "[a-z]{1,8}"
+    }
+
+    fn cube_set_strategy() -> impl Strategy<Value = CubeSet> {
+        prop::collection::btree_map(prop::sample::select(vec!["red", "green", "blue", "cyan"]), 0u32..20, 0..4)
+            .prop_map(|counts| counts.into_iter().fold(CubeSet::new(), |set, (colour, count)| set.with(colour, count)))
     }
 
     proptest! {
@@ -166,15 +212,15 @@ mod test {
 
     proptest! {
         #[test]
-        fn test_parse_color(expected_color in color_enum_strateg()) {
-            let (_, actual_color) = parse_color(&expected_color.to_string()).unwrap();
+        fn test_parse_color(expected_color in color_strategy()) {
+            let (_, actual_color) = parse_color(&expected_color.to_uppercase()).unwrap();
             assert_eq!(expected_color, actual_color);
         }
     }
 
     proptest! {
         #[test]
-        fn test_parse_dice_pull(expected_pull_count in 0u32..u32::MAX, expected_color in color_enum_strateg()) {
+        fn test_parse_dice_pull(expected_pull_count in 0u32..u32::MAX, expected_color in color_strategy()) {
             let line = format!("{expected_pull_count} {}", expected_color);
             let (_, (actual_pull_count, actual_color)) = parse_pull_part(&line).unwrap();
 
@@ -183,10 +229,10 @@ mod test {
         }
     }
 
-    #[test_case("3 blue, 4 red", Pull::new(Some(Red(4)), Some(Blue(3)), None))]
-    #[test_case("1 red, 2 green, 6 blue", Pull::new(Some(Red(1)), Some(Blue(6)), Some(Green(2))))]
-    #[test_case("2 green", Pull::new(None, None, Some(Green(2))))]
-    fn test_parse_full_pull(line: &str, expected_pull: Pull) {
+    #[test_case("3 blue, 4 red", CubeSet::new().with("red", 4).with("blue", 3))]
+    #[test_case("1 red, 2 green, 6 blue", CubeSet::new().with("red", 1).with("blue", 6).with("green", 2))]
+    #[test_case("2 green", CubeSet::new().with("green", 2))]
+    fn test_parse_full_pull(line: &str, expected_pull: CubeSet) {
         let (_, actual_pull) = parse_full_pull(line).unwrap();
         assert_eq!(expected_pull, actual_pull);
     }
@@ -196,9 +242,9 @@ mod test {
         Game::new(
             1, 
             vec![
-                Pull::new(Some(Red(4)), Some(Blue(3)), None), 
-                Pull::new(Some(Red(1)), Some(Blue(6)), Some(Green(2))),
-                Pull::new(None, None, Some(Green(2)))
+                CubeSet::new().with("red", 4).with("blue", 3), 
+                CubeSet::new().with("red", 1).with("blue", 6).with("green", 2),
+                CubeSet::new().with("green", 2)
             ]
         )
     )]
@@ -207,9 +253,9 @@ mod test {
         Game::new(
             2,
             vec![
-                Pull::new(None, Some(Blue(1)), Some(Green(2))),
-                Pull::new(Some(Red(1)), Some(Blue(4)), Some(Green(3))),
-                Pull::new(None, Some(Blue(1)), Some(Green(1)))
+                CubeSet::new().with("blue", 1).with("green", 2),
+                CubeSet::new().with("red", 1).with("blue", 4).with("green", 3),
+                CubeSet::new().with("blue", 1).with("green", 1)
             ]
         )
     )]
@@ -218,9 +264,9 @@ mod test {
         Game::new(
             3,
             vec![
-                Pull::new(Some(Red(20)), Some(Blue(6)), Some(Green(8))),
-                Pull::new(Some(Red(4)), Some(Blue(5)), Some(Green(13))),
-                Pull::new(Some(Red(1)), None, Some(Green(5)))
+                CubeSet::new().with("red", 20).with("blue", 6).with("green", 8),
+                CubeSet::new().with("red", 4).with("blue", 5).with("green", 13),
+                CubeSet::new().with("red", 1).with("green", 5)
             ]
         )
     )]
@@ -229,9 +275,9 @@ mod test {
         Game::new(
             4,
             vec![
-                Pull::new(Some(Red(3)), Some(Blue(6)), Some(Green(1))),
-                Pull::new(Some(Red(6)), None, Some(Green(3))),
-                Pull::new(Some(Red(14)), Some(Blue(15)), Some(Green(3)))
+                CubeSet::new().with("red", 3).with("blue", 6).with("green", 1),
+                CubeSet::new().with("red", 6).with("green", 3),
+                CubeSet::new().with("red", 14).with("blue", 15).with("green", 3)
             ]
         )
     )]
@@ -240,8 +286,8 @@ mod test {
         Game::new(
             5,
             vec![
-                Pull::new(Some(Red(6)), Some(Blue(1)), Some(Green(3))),
-                Pull::new(Some(Red(1)), Some(Blue(2)), Some(Green(2)))
+                CubeSet::new().with("red", 6).with("blue", 1).with("green", 3),
+                CubeSet::new().with("red", 1).with("blue", 2).with("green", 2)
             ]
         )
     )]
@@ -259,59 +305,118 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green        
 "#;
-        let (_, games) = parse_games(input).unwrap();
+        let games = parse_games(input, &Palette::standard()).unwrap();
 
         let expected_games = vec![
             Game::new(
                 1, 
                 vec![
-                    Pull::new(Some(Red(4)), Some(Blue(3)), None), 
-                    Pull::new(Some(Red(1)), Some(Blue(6)), Some(Green(2))),
-                    Pull::new(None, None, Some(Green(2)))
+                    CubeSet::new().with("red", 4).with("blue", 3), 
+                    CubeSet::new().with("red", 1).with("blue", 6).with("green", 2),
+                    CubeSet::new().with("green", 2)
                 ]
             ),
             Game::new(
                 2,
                 vec![
-                    Pull::new(None, Some(Blue(1)), Some(Green(2))),
-                    Pull::new(Some(Red(1)), Some(Blue(4)), Some(Green(3))),
-                    Pull::new(None, Some(Blue(1)), Some(Green(1)))
+                    CubeSet::new().with("blue", 1).with("green", 2),
+                    CubeSet::new().with("red", 1).with("blue", 4).with("green", 3),
+                    CubeSet::new().with("blue", 1).with("green", 1)
                 ]
             ),
             Game::new(
                 3,
                 vec![
-                    Pull::new(Some(Red(20)), Some(Blue(6)), Some(Green(8))),
-                    Pull::new(Some(Red(4)), Some(Blue(5)), Some(Green(13))),
-                    Pull::new(Some(Red(1)), None, Some(Green(5)))
+                    CubeSet::new().with("red", 20).with("blue", 6).with("green", 8),
+                    CubeSet::new().with("red", 4).with("blue", 5).with("green", 13),
+                    CubeSet::new().with("red", 1).with("green", 5)
                 ]
             ),
             Game::new(
                 4,
                 vec![
-                    Pull::new(Some(Red(3)), Some(Blue(6)), Some(Green(1))),
-                    Pull::new(Some(Red(6)), None, Some(Green(3))),
-                    Pull::new(Some(Red(14)), Some(Blue(15)), Some(Green(3)))
+                    CubeSet::new().with("red", 3).with("blue", 6).with("green", 1),
+                    CubeSet::new().with("red", 6).with("green", 3),
+                    CubeSet::new().with("red", 14).with("blue", 15).with("green", 3)
                 ]
             ),
             Game::new(
                 5,
                 vec![
-                    Pull::new(Some(Red(6)), Some(Blue(1)), Some(Green(3))),
-                    Pull::new(Some(Red(1)), Some(Blue(2)), Some(Green(2)))
+                    CubeSet::new().with("red", 6).with("blue", 1).with("green", 3),
+                    CubeSet::new().with("red", 1).with("blue", 2).with("green", 2)
                 ]
             ),
         ];
 
         assert_eq!(games, expected_games);
 
-        let reference_pull = Pull::new(Some(Red(12)), Some(Blue(14)), Some(Green(13)));
+        let bag = CubeSet::new().with("red", 12).with("blue", 14).with("green", 13);
         let sum_of_ids: u32 = games.iter()
-            .filter(|g| g.pulls.iter().all(|p| p <= &reference_pull))
+            .filter(|g| g.is_possible(&bag))
             .map(|g| g.id)
             .sum();
 
         assert_eq!(8, sum_of_ids);
 
+        let power: u64 = games.iter()
+            .map(|g| g.fewest_cubes(&Palette::standard()).power())
+            .sum();
+
+        assert_eq!(2286, power);
+
+    }
+
+    #[test]
+    fn test_incomparable_sets() {
+        let more_red = CubeSet::new().with("red", 5).with("blue", 1);
+        let more_blue = CubeSet::new().with("red", 1).with("blue", 5);
+
+        assert!(!more_red.fits_within(&more_blue));
+        assert!(!more_blue.fits_within(&more_red));
+        assert_eq!(more_red.union(&more_blue), CubeSet::new().with("red", 5).with("blue", 5));
+    }
+
+    #[test]
+    fn test_fewest_cubes() {
+        let (_, game) = parse_game("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+        let fewest = game.fewest_cubes(&Palette::standard());
+
+        assert_eq!(fewest, CubeSet::new().with("red", 4).with("green", 2).with("blue", 6));
+        assert_eq!(fewest.power(), 48);
+
+        // no green at all makes for no power
+        let (_, game) = parse_game("Game 2: 3 blue, 4 red").unwrap();
+        assert_eq!(game.fewest_cubes(&Palette::standard()).power(), 0);
+    }
+
+    #[test]
+    fn test_custom_palette() {
+        let palette = Palette::new(["Cyan", "magenta"]);
+        let games = parse_games("Game 7: 2 cyan; 3 magenta, 1 CYAN", &palette).unwrap();
+
+        assert_eq!(games[0].fewest_cubes(&palette), CubeSet::new().with("cyan", 2).with("magenta", 3));
+        assert!(games[0].is_possible(&CubeSet::new().with("cyan", 2).with("magenta", 3)));
+        assert!(!games[0].is_possible(&CubeSet::new().with("cyan", 2).with("magenta", 2)));
+    }
+
+    #[test_case("Game 1: 3 blue\nGame 2: 4 purple, 1 red", GameError::UnknownColour { game: 2, colour: "purple".to_string() })]
+    #[test_case("Game 1: 3 blue\n\nGame 2 4 red", GameError::Malformed { line: 3 })]
+    #[test_case("Game 1: 3 blue, red", GameError::Malformed { line: 1 })]
+    #[test_case("Game 1: 3 red, 4 red", GameError::Malformed { line: 1 }; "repeated colour")]
+    #[test_case("Game 1: 1 blue\nGame 2: 2 green; 3 red, 1 blue, 4 red", GameError::Malformed { line: 2 }; "repeated colour in a later pull")]
+    fn test_parse_games_errors(input: &str, expected: GameError) {
+        assert_eq!(parse_games(input, &Palette::standard()), Err(expected));
+    }
+
+    proptest! {
+        #[test]
+        fn test_union_is_least_upper_bound(a in cube_set_strategy(), b in cube_set_strategy(), bag in cube_set_strategy()) {
+            let union = a.union(&b);
+
+            prop_assert_eq!(&union, &b.union(&a));
+            prop_assert!(a.fits_within(&union) && b.fits_within(&union));
+            prop_assert_eq!(union.fits_within(&bag), a.fits_within(&bag) && b.fits_within(&bag));
+        }
     }
 }
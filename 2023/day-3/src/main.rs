@@ -1,8 +1,106 @@
+use std::ops::Range;
+
 use nom::{IResult, character::{complete::newline, is_newline}, multi::{many0, separated_list0}, bytes::complete::take_till, combinator::map_parser};
 
 fn main() {
     let input = include_bytes!("input.txt");
     let (_, schematic) = parse_schematic(input).unwrap();
+
+    let part_sum = part_numbers(&schematic).iter().sum::<u32>();
+    println!("{part_sum}");
+
+    let gear_ratio_sum = gears(&schematic).iter().map(Gear::ratio).sum::<u32>();
+    println!("{gear_ratio_sum}");
+}
+
+/// A number as written in the schematic, which may span several columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NumberSpan {
+    value: u32,
+    row: usize,
+    cols: Range<usize>,
+}
+
+impl NumberSpan {
+    /// Whether `(row, col)` touches the number, diagonals included.
+    fn is_adjacent(&self, row: usize, col: usize) -> bool {
+        row + 1 >= self.row && row <= self.row + 1
+            && col + 1 >= self.cols.start && col <= self.cols.end
+    }
+}
+
+/// A `*` next to exactly two numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Gear {
+    row: usize,
+    col: usize,
+    numbers: (u32, u32),
+}
+
+impl Gear {
+    fn ratio(&self) -> u32 {
+        self.numbers.0 * self.numbers.1
+    }
+}
+
+fn number_spans(schematic: &[Vec<GridEntry>]) -> Vec<NumberSpan> {
+    let mut spans = Vec::new();
+
+    for (row, line) in schematic.iter().enumerate() {
+        let mut current: Option<NumberSpan> = None;
+
+        for (col, entry) in line.iter().enumerate() {
+            match (entry, current.as_mut()) {
+                (GridEntry::Number(digit), Some(span)) => {
+                    span.value = span.value * 10 + digit.to_digit(10).unwrap();
+                    span.cols.end = col + 1;
+                }
+                (GridEntry::Number(digit), None) => {
+                    current = Some(NumberSpan { value: digit.to_digit(10).unwrap(), row, cols: col..col + 1 });
+                }
+                (_, _) => spans.extend(current.take()),
+            }
+        }
+
+        spans.extend(current);
+    }
+
+    spans
+}
+
+fn symbols(schematic: &[Vec<GridEntry>]) -> impl Iterator<Item = (usize, usize, char)> + '_ {
+    schematic.iter().enumerate().flat_map(|(row, line)| {
+        line.iter().enumerate().filter_map(move |(col, entry)| match entry {
+            GridEntry::Symbol(symbol) => Some((row, col, *symbol)),
+            _ => None,
+        })
+    })
+}
+
+/// Every number touching a symbol, in reading order.
+fn part_numbers(schematic: &[Vec<GridEntry>]) -> Vec<u32> {
+    let symbols = symbols(schematic).collect::<Vec<_>>();
+
+    number_spans(schematic)
+        .into_iter()
+        .filter(|span| symbols.iter().any(|&(row, col, _)| span.is_adjacent(row, col)))
+        .map(|span| span.value)
+        .collect()
+}
+
+fn gears(schematic: &[Vec<GridEntry>]) -> Vec<Gear> {
+    let spans = number_spans(schematic);
+
+    symbols(schematic)
+        .filter(|&(_, _, symbol)| symbol == '*')
+        .filter_map(|(row, col, _)| {
+            let mut adjacent = spans.iter().filter(|span| span.is_adjacent(row, col));
+            match (adjacent.next(), adjacent.next(), adjacent.next()) {
+                (Some(a), Some(b), None) => Some(Gear { row, col, numbers: (a.value, b.value) }),
+                _ => None,
+            }
+        })
+        .collect()
 }
 
 fn parse_schematic(i: &[u8]) -> IResult<&[u8], Vec<Vec<GridEntry>>> {
@@ -22,7 +120,7 @@ fn parse_member(i: &[u8]) -> IResult<&[u8], GridEntry> {
         Ok((&i[1..], match i[0] {
             b'.' => GridEntry::Empty,
             c if (c as char).is_digit(10) => GridEntry::Number(c as char),
-            _ => GridEntry::Symbol 
+            c => GridEntry::Symbol(c as char),
         }))
     }
 }
@@ -30,9 +128,8 @@ fn parse_member(i: &[u8]) -> IResult<&[u8], GridEntry> {
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 enum GridEntry {
     Empty,
-    Symbol,
+    Symbol(char),
     Number(char),
-    
 }
 
 #[cfg(test)]
@@ -42,7 +139,8 @@ mod test {
     use test_case::test_case;
 
     #[test_case(".".as_bytes(), GridEntry::Empty)]
-    #[test_case("*".as_bytes(), GridEntry::Symbol)]
+    #[test_case("*".as_bytes(), GridEntry::Symbol('*'))]
+    #[test_case("#".as_bytes(), GridEntry::Symbol('#'))]
     #[test_case("6".as_bytes(), GridEntry::Number('6'))]
     fn test_parse_entry(i: &[u8], expected: GridEntry) {
         let (rem, actual) = parse_member(i).unwrap();
@@ -82,18 +180,80 @@ mod test {
 
         let expected = vec![
             vec![Number('4'),Number('6'),Number('7'),Empty,Empty,Number('1'),Number('1'),Number('4'),Empty,Empty,],
-            vec![Empty,Empty,Empty,Symbol,Empty,Empty,Empty,Empty,Empty,Empty,],
+            vec![Empty,Empty,Empty,Symbol('*'),Empty,Empty,Empty,Empty,Empty,Empty,],
             vec![Empty,Empty,Number('3'),Number('5'),Empty,Empty,Number('6'),Number('3'),Number('3'),Empty],
-            vec![Empty,Empty,Empty,Empty,Empty,Empty,Symbol,Empty,Empty,Empty,],
-            vec![Number('6'),Number('1'),Number('7'),Symbol,Empty,Empty,Empty,Empty,Empty,Empty,],
-            vec![Empty,Empty,Empty,Empty,Empty,Symbol,Empty,Number('5'), Number('8'), Empty,],
+            vec![Empty,Empty,Empty,Empty,Empty,Empty,Symbol('#'),Empty,Empty,Empty,],
+            vec![Number('6'),Number('1'),Number('7'),Symbol('*'),Empty,Empty,Empty,Empty,Empty,Empty,],
+            vec![Empty,Empty,Empty,Empty,Empty,Symbol('+'),Empty,Number('5'), Number('8'), Empty,],
             vec![Empty,Empty,Number('5'),Number('9'),Number('2'),Empty,Empty,Empty,Empty,Empty,],
             vec![Empty,Empty,Empty,Empty,Empty,Empty,Number('7'),Number('5'),Number('5'),Empty],
-            vec![Empty,Empty,Empty,Symbol,Empty,Symbol,Empty,Empty,Empty,Empty],
+            vec![Empty,Empty,Empty,Symbol('$'),Empty,Symbol('*'),Empty,Empty,Empty,Empty],
             vec![Empty,Number('6'),Number('6'),Number('4'),Empty,Number('5'),Number('9'),Number('8'),Empty,Empty]
         ];
 
         let (_, actual) = parse_schematic(input.as_bytes()).unwrap();
         assert_eq!(expected, actual);
     }
-}
\ No newline at end of file
+
+    const SAMPLE: &str = r#"467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598.."#;
+
+    #[test]
+    fn test_number_spans() {
+        let (_, schematic) = parse_schematic(SAMPLE.as_bytes()).unwrap();
+        let spans = number_spans(&schematic);
+
+        assert_eq!(spans.len(), 10);
+        assert_eq!(spans[0], NumberSpan { value: 467, row: 0, cols: 0..3 });
+        assert_eq!(spans[1], NumberSpan { value: 114, row: 0, cols: 5..8 });
+        assert_eq!(spans[9], NumberSpan { value: 598, row: 9, cols: 5..8 });
+    }
+
+    #[test_case("123", vec![NumberSpan { value: 123, row: 0, cols: 0..3 }])]
+    #[test_case("1.2", vec![NumberSpan { value: 1, row: 0, cols: 0..1 }, NumberSpan { value: 2, row: 0, cols: 2..3 }])]
+    #[test_case("12\n34", vec![NumberSpan { value: 12, row: 0, cols: 0..2 }, NumberSpan { value: 34, row: 1, cols: 0..2 }])]
+    #[test_case("4*5", vec![NumberSpan { value: 4, row: 0, cols: 0..1 }, NumberSpan { value: 5, row: 0, cols: 2..3 }])]
+    fn test_number_spans_break(input: &str, expected: Vec<NumberSpan>) {
+        let (_, schematic) = parse_schematic(input.as_bytes()).unwrap();
+        assert_eq!(number_spans(&schematic), expected);
+    }
+
+    #[test_case(0, 0, true)]
+    #[test_case(0, 4, true)]
+    #[test_case(2, 1, true)]
+    #[test_case(1, 5, false)]
+    #[test_case(3, 2, false)]
+    fn test_adjacency(row: usize, col: usize, expected: bool) {
+        let span = NumberSpan { value: 123, row: 1, cols: 1..4 };
+        assert_eq!(span.is_adjacent(row, col), expected);
+    }
+
+    #[test]
+    fn test_part_numbers() {
+        let (_, schematic) = parse_schematic(SAMPLE.as_bytes()).unwrap();
+        let parts = part_numbers(&schematic);
+
+        assert!(!parts.contains(&114) && !parts.contains(&58));
+        assert_eq!(parts.iter().sum::<u32>(), 4361);
+    }
+
+    #[test]
+    fn test_gears() {
+        let (_, schematic) = parse_schematic(SAMPLE.as_bytes()).unwrap();
+        let gears = gears(&schematic);
+
+        assert_eq!(gears, vec![
+            Gear { row: 1, col: 3, numbers: (467, 35) },
+            Gear { row: 8, col: 5, numbers: (755, 598) },
+        ]);
+        assert_eq!(gears.iter().map(Gear::ratio).sum::<u32>(), 467835);
+    }
+}
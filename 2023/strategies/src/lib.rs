@@ -0,0 +1,21 @@
+//! proptest strategies shared between the 2023 solutions' tests.
+
+use proptest::prelude::*;
+
+/// Day 1 lines made of digit words, pieces of them and digits, with at least
+/// one digit somewhere.
+pub fn calibration_line() -> impl Strategy<Value = String> {
+    let piece = prop::sample::select(vec![
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "on", "tw",
+        "thre", "fou", "fiv", "seve", "eigh", "nin", "ne", "wo", "ight", "e", "n", "o", "t", "x",
+        "0", "1", "5", "9",
+    ]);
+    let digit = prop::sample::select(vec!["zero", "three", "eight", "nine", "0", "7"]);
+
+    (
+        prop::collection::vec(piece.clone(), 0..8),
+        digit,
+        prop::collection::vec(piece, 0..8),
+    )
+        .prop_map(|(before, digit, after)| before.concat() + digit + &after.concat())
+}
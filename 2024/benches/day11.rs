@@ -0,0 +1,30 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+use aoc_2024::day11::{blink, memoized_blink, StoneLine};
+
+const STONES: [u64; 8] = [0, 7, 125, 17, 4048, 2024, 99999, 3_000_000];
+
+fn day11_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day11 blinks");
+
+    // the plain recursion doubles with every split, so it can't go much further
+    group.bench_function("recursive 25", |b| {
+        b.iter(|| black_box(STONES).iter().map(|&stone| blink(stone, 0, 25)).sum::<usize>())
+    });
+
+    for blinks in [25, 75] {
+        group.bench_function(format!("memoized per stone {blinks}"), |b| {
+            b.iter(|| black_box(STONES).iter().map(|&stone| memoized_blink(stone, blinks)).sum::<usize>())
+        });
+
+        group.bench_function(format!("stone line {blinks}"), |b| {
+            b.iter(|| StoneLine::new(black_box(&STONES)).after(blinks).len())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, day11_bench);
+criterion_main!(benches);
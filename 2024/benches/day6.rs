@@ -0,0 +1,48 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+use aoc_2024::day6::{count_loop_obstacles, count_loop_obstacles_rewalk, parser};
+
+/// A square map with roughly one rock in `sparsity` cells and the guard in
+/// the middle. Seeded so every run benches the same map.
+fn generate_map(size: usize, sparsity: u32, mut seed: u32) -> String {
+    (0..size)
+        .map(|row| {
+            (0..size)
+                .map(|col| {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                    if (row, col) == (size / 2, size / 2) {
+                        '^'
+                    } else if (seed >> 16).is_multiple_of(sparsity) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn day6_bench(c: &mut Criterion) {
+    // this seed gives a loop-free patrol of ~2000 cells
+    let input = generate_map(200, 40, 4);
+    let (_, world) = parser::world(&input).unwrap();
+
+    let mut group = c.benchmark_group("day6 loop obstacles 200x200");
+    group.sample_size(10);
+
+    group.bench_function("rewalk", |b| {
+        b.iter(|| count_loop_obstacles_rewalk(black_box(&world)))
+    });
+
+    group.bench_function("jump table", |b| {
+        b.iter(|| count_loop_obstacles(black_box(&world)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, day6_bench);
+criterion_main!(benches);
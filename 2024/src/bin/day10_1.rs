@@ -5,7 +5,7 @@ pub fn main() {
     println!("{}", compute(input));
 }
 
-fn compute(input: &str) -> u32 {
+fn compute(input: &str) -> usize {
     let map = parse(input);
     trailhead_scores(&map).iter().sum()
 }
@@ -29,6 +29,6 @@ mod test {
 01329801
 10456732"#;
         
-        assert_eq!(36u32, super::compute(input));
+        assert_eq!(36, super::compute(input));
     }
 }
\ No newline at end of file
@@ -1,11 +1,11 @@
-use aoc_2024::day10::{parser::parse, trailhead_ratings, trailhead_scores};
+use aoc_2024::day10::{parser::parse, trailhead_ratings};
 
 pub fn main() {
     let input = include_str!("../../data/day10.txt");
     println!("{}", compute(input));
 }
 
-fn compute(input: &str) -> u32 {
+fn compute(input: &str) -> u64 {
     let map = parse(input);
     trailhead_ratings(&map).iter().sum()
 }
@@ -1,18 +1,12 @@
-use aoc_2024::day11::{memoized_blink, parser::parse};
-use iter::{IntoParallelRefIterator, ParallelIterator};
-use rayon::*;
+use aoc_2024::day11::{parser::parse, StoneLine};
 
 pub fn main() {
     let input = include_str!("../../data/day11.txt");
-    println!("{}", compute(input));
+    println!("{}", compute(input, 75));
 }
 
-fn compute(input: &str) -> usize {
-    const TARGET_BLINKS: usize = 75;
-
-    let init = parse(input);
-    
-    init.par_iter().map(|stone| memoized_blink(*stone,  TARGET_BLINKS)).sum()
+fn compute(input: &str, blinks: usize) -> usize {
+    StoneLine::new(&parse(input)).after(blinks).len()
 }
 
 #[cfg(test)]
@@ -22,6 +16,6 @@ mod test {
     #[test]
     fn test_sample_input() {
         let input = "125 17";
-        assert_eq!(compute(input), 55312);
+        assert_eq!(compute(input, 25), 55312);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,31 @@
+use aoc_2024::day12::parser::parse;
+
+pub fn main() {
+    let input = include_str!("../../data/day12.txt");
+    println!("{}", compute(input));
+}
+
+fn compute(input: &str) -> usize {
+    parse(input).total_bulk_price()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::compute;
+
+    #[test]
+    fn test_sample_input() {
+        let input = r#"RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE"#;
+
+        assert_eq!(compute(input), 1206);
+    }
+}
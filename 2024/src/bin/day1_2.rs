@@ -1,7 +1,4 @@
-use std::collections::HashMap;
-
-use aoc_2024::day1::parser::parse_nums;
-use rustc_hash::FxBuildHasher;
+use aoc_2024::day1::{parser::parse_lists, similarity_score};
 
 pub fn main() {
     let input = include_str!("../../data/day1.txt");
@@ -9,23 +6,7 @@ pub fn main() {
 }
 
 fn day1_2(input: &str) -> i32 {
-    let mut counts = HashMap::with_capacity_and_hasher(1024, FxBuildHasher::default());
-
-    let l1: Vec<_> = input
-        .lines()
-        .flat_map(|line| parse_nums(line))
-        .map(|(_, pair)| {
-            *counts.entry(pair.1).or_insert(0) += 1;
-            pair.0
-        })
-        .fold(Vec::with_capacity(1024), |mut acc, next| {
-            acc.push(next);
-            acc
-        });
-
-    l1.iter()
-        .filter_map(|l| counts.get(&l).map(|c| c * l))
-        .sum()
+    similarity_score(&parse_lists(input))
 }
 
 #[cfg(test)]
@@ -1,5 +1,4 @@
-use aoc_2024::day3::parser;
-
+use aoc_2024::day3::{scan, InstructionTable, Interpreter, Ungated};
 
 pub fn main() {
     let input = include_str!("../../data/day3.txt");
@@ -7,12 +6,8 @@ pub fn main() {
 }
 
 fn compute(input: &str) -> i64 {
-    parser::parse_muls(input)
-        .unwrap()
-        .1
-        .iter()
-        .map(|(a, b)| a * b)
-        .sum::<i64>()
+    let table = InstructionTable::new().with("mul", 2);
+    Interpreter::multiplier(Ungated).run(scan(input, &table))
 }
 
 #[cfg(test)]
@@ -25,4 +20,4 @@ mod test {
         let actual = compute(input);
         assert_eq!(actual, 161);
     }
-}
\ No newline at end of file
+}
@@ -1,4 +1,4 @@
-use aoc_2024::day3::parser;
+use aoc_2024::day3::{scan, DoDont, InstructionTable, Interpreter};
 
 pub fn main() {
     let input = include_str!("../../data/day3.txt");
@@ -6,7 +6,8 @@ pub fn main() {
 }
 
 fn compute(input: &str) -> i64 {
-    parser::parse_and_compute(input).unwrap().1
+    let table = InstructionTable::corrupted_memory();
+    Interpreter::multiplier(DoDont::default()).run(scan(input, &table))
 }
 
 #[cfg(test)]
@@ -1,4 +1,4 @@
-use aoc_2024::day5::{self, Page};
+use aoc_2024::day5;
 
 pub fn main() {
     let input = include_str!("../../data/day5.txt");
@@ -10,17 +10,8 @@ pub fn main() {
 fn compute(input: &str) -> u32 {
     let (_, (rules, updates)) = day5::parser::input_sections(input).unwrap();
     updates.into_iter()
-        .map(|update| {
-            (
-                update.len(),
-                update.into_iter()
-                    .map(|pg| Page::new(pg, &rules))
-                    .collect::<Vec<_>>()
-            )
-        })
-        .filter(|(_, update)| update.is_sorted())
-        .map(|(len, update)| update[len/2])
-        .map(|page| page.number())
+        .filter(|update| day5::is_ordered(&rules, update))
+        .filter_map(|update| day5::middle_page(&update))
         .sum::<u32>()
 }
 
@@ -61,6 +52,6 @@ mod test {
 
     let v = compute(input);
 
-    assert_eq!(v, 144);
+    assert_eq!(v, 143);
     }
 }
\ No newline at end of file
@@ -1,5 +1,4 @@
-use aoc_2024::day5::{self, Page};
-use itertools::Itertools;
+use aoc_2024::day5;
 
 pub fn main() {
     let input = include_str!("../../data/day5.txt");
@@ -11,16 +10,9 @@ pub fn main() {
 fn compute(input: &str) -> u32 {
     let (_, (rules, updates)) = day5::parser::input_sections(input).unwrap();
     updates.into_iter()
-        .map(|update| {
-            update.into_iter()
-                .map(|pg| Page::new(pg, &rules))
-        })
-        .filter(|update| !update.clone().is_sorted())
-        .map(|update| {
-            update.sorted_unstable()
-        })
-        .flat_map(|mut update| update.nth(update.len() / 2))
-        .map(|page| page.number())
+        .filter(|update| !day5::is_ordered(&rules, update))
+        .map(|update| day5::fix_update(&rules, &update).unwrap())
+        .filter_map(|update| day5::middle_page(&update))
         .sum::<u32>()
 }
 
@@ -1,6 +1,4 @@
-use std::collections::HashSet;
-
-use aoc_2024::day6::{parser, Position};
+use aoc_2024::day6::{parser, patrolled_positions};
 
 pub fn main() {
     let input = include_str!("../../data/day6.txt");
@@ -10,27 +8,8 @@ pub fn main() {
 }
 
 fn compute(input: &str) -> usize {
-    let (_, mut world) = parser::world(input).unwrap();
-    let mut distinct = HashSet::<Position>::from([
-        *world.guard_location()
-    ]);
-
-    loop {
-        let start = *world.guard_location();
-        let res = world.walk_guard();
-        let guard_pos = match res {
-            Ok(pos) => pos,
-            Err(pos) => pos,
-        };
-
-        distinct.extend(start.to(&guard_pos));
-
-        if res.is_err() {
-            break;
-        }
-    }
-
-    return distinct.len()
+    let (_, world) = parser::world(input).unwrap();
+    patrolled_positions(&world).len()
 }
 
 #[cfg(test)]
@@ -52,4 +31,4 @@ mod test {
         let actual = super::compute(input);
         assert_eq!(actual, 41);
     }
-}
\ No newline at end of file
+}
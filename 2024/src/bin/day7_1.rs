@@ -1,5 +1,4 @@
-use aoc_2024::day7::{parser::parse_lines, Token};
-use itertools::Itertools;
+use aoc_2024::day7::{parser::parse_lines, total_calibration, Operator};
 
 pub fn main() {
     let input = include_str!("../../data/day7.txt");
@@ -9,49 +8,8 @@ pub fn main() {
 }
 
 fn compute(input: &str) -> u64 {
- let (_, lines) = parse_lines(input).unwrap();
- lines.into_iter()
-    .filter(|(output, line)| {
-        (0..line.len() - 1)
-            .map(|_| vec![Token::Operation(u64::saturating_mul), Token::Operation(u64::saturating_add)])
-            .multi_cartesian_product()
-            .map(|ops| vec![Token::Operation(u64::saturating_add)].into_iter().chain(ops.into_iter()).interleave(line.into_iter().map(|&v| Token::Operand(v))))
-            .any(|chain| {
-                chain.chunks(2).into_iter()
-                .flat_map(|chunk| chunk.tuples::<(_, _)>())
-                // .inspect(|pair| { dbg!(pair); })
-                .fold(0u64, |acc, (operation, operand)| {
-                    let func = match operation {
-                        Token::Operation(func) => func,
-                        _ => panic!("first value must be an operation")
-                    };
-                    let rhs = match operand {
-                        Token::Operand(value) => value,
-                        _ => panic!("second value must be an operand")
-                    };
-
-                    func(acc, rhs)
-                }) == *output
-            })
-        })
-            // .chunks(2)
-            // .into_iter()
-            // .flat_map(|chunk| chunk.tuples::<(_, _)>())
-            // .inspect(|pair| { dbg!(&pair); })
-            // .fold(0u64, |acc, (operation, operand)| {
-            //     let func = match operation {
-            //         Token::Operation(func) => func,
-            //         _ => panic!("first value must be an operation")
-            //     };
-            //     let rhs = match operand {
-            //         Token::Operand(value) => value,
-            //         _ => panic!("second value must be an operand")
-            //     };
-
-            //     func(acc, rhs)
-            // }) == *output
-    .map(|(output, _)| output)
-    .sum()
+    let (_, lines) = parse_lines(input).unwrap();
+    total_calibration(&lines, &Operator::PART_1)
 }
 
 #[cfg(test)]
@@ -72,4 +30,4 @@ mod test {
 "#;
         assert_eq!(compute(input), 3749)
     }
-}
\ No newline at end of file
+}
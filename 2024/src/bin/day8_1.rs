@@ -1,26 +1,14 @@
-use aoc_2024::day8::PointPair;
-use itertools::Itertools;
+use aoc_2024::day8::{parser, AntinodeMode};
 
 pub fn main() {
     let input = include_str!("../../data/day8.txt");
-    let antinodes = compute(input, (50, 50));
+    let antinodes = compute(input);
 
     println!("{antinodes}");
 }
 
-fn compute(input: &str, grid_size: (i64, i64)) -> usize {
-    aoc_2024::day8::parser::parse(input)
-        .into_iter()
-        .flat_map(|(_, points)| {
-            points.iter()
-                .tuple_combinations::<(_, _)>()
-                .map(|(a, b)| PointPair(a.clone(), b.clone()))
-                .flat_map(|pair| pair.antinodes(grid_size))
-                .filter(|point| point.x() >= 0 && point.y() >= 0 && point.x() < grid_size.0 && point.y() < grid_size.1)
-                .collect::<Vec<_>>()
-        })
-        .unique()
-        .count()
+fn compute(input: &str) -> usize {
+    parser::parse(input).antinodes(AntinodeMode::TwoAntinodes).len()
 }
 
 #[cfg(test)]
@@ -30,7 +18,7 @@ mod test {
     #[test]
     fn test_solution() {
         let input = include_str!("../../data/day8.txt");
-        let antinodes = compute(input, (50, 50));
+        let antinodes = compute(input);
 
         assert_eq!(antinodes, 376);
     }
@@ -49,7 +37,7 @@ mod test {
 .........A..
 ............
 ............"#;
-        let actual = compute(input, (12, 12));
+        let actual = compute(input);
         assert_eq!(actual, 14);
     }
 }
\ No newline at end of file
@@ -1,28 +1,14 @@
-use aoc_2024::day8::PointPair;
-use itertools::Itertools;
+use aoc_2024::day8::{parser, AntinodeMode};
 
 pub fn main() {
     let input = include_str!("../../data/day8.txt");
-    let antinodes = compute(input, (50, 50));
+    let antinodes = compute(input);
 
     println!("{antinodes}");
 }
 
-fn compute(input: &str, grid_size: (i64, i64)) -> usize {
-    aoc_2024::day8::parser::parse(input)
-        .into_iter()
-        .flat_map(|(_, points)| {
-            points.iter()
-                .tuple_combinations::<(_, _)>()
-                .map(|(a, b)| PointPair(a.clone(), b.clone()))
-                .flat_map(|pair| pair.antinodes(grid_size))
-                .collect::<Vec<_>>()
-        })
-        .unique()
-        .sorted()
-        // .sorted_by(|p1, p2| p1.y().cmp(&p2.y()))
-        // .inspect(|p| { println!("{p:?}"); })
-        .count()
+fn compute(input: &str) -> usize {
+    parser::parse(input).antinodes(AntinodeMode::ResonantHarmonics).len()
 }
 
 #[cfg(test)]
@@ -31,20 +17,20 @@ mod test {
     use test_case::test_case;
 
 
-    #[test_case("00...", (5,1), 5)]
-    #[test_case("0\n0\n.\n.\n.", (1, 5), 5)]
-    #[test_case(".\n.\na\na\n.", (1, 5), 5)]
-    #[test_case("0....\n.0...", (5, 5), 5)]
-    #[test_case("....a\n...a.", (5, 5), 5)]
-    fn test_contrinved_input(input: &str, grid_size: (i64, i64), expected: usize) {
-        let actual = compute(input, grid_size);
+    #[test_case("00...", 5)]
+    #[test_case("0\n0\n.\n.\n.", 5)]
+    #[test_case(".\n.\na\na\n.", 5)]
+    #[test_case("0....\n.0...\n.....\n.....\n.....", 5)]
+    #[test_case("....a\n...a.\n.....\n.....\n.....", 5)]
+    fn test_contrinved_input(input: &str, expected: usize) {
+        let actual = compute(input);
         assert_eq!(actual, expected);
     }
 
     #[test]
     fn test_real_input_bound() {
         let input = include_str!("../../data/day8.txt");
-        let antinodes = compute(input, (50, 50));
+        let antinodes = compute(input);
         assert_eq!(antinodes, 1352);
     }
 
@@ -61,7 +47,7 @@ mod test {
 ....#.....
 .........."#;
 
-            let actual = compute(input, (10,10));
+            let actual = compute(input);
             assert_eq!(actual, 9);
         }
 
@@ -79,20 +65,7 @@ mod test {
 .........A..
 ............
 ............"#;
-        let actual = compute(input, (12, 12));
+        let actual = compute(input);
         assert_eq!(actual, 34);
-
-        r#"
-$....$*...
-...$......
-.$....$...
-.........$
-..$.......
-..........
-...$......
-..........
-....$.....
-..........
-"#;
     }
 }
\ No newline at end of file
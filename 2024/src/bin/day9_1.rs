@@ -9,7 +9,7 @@ pub fn main() {
 
 fn compute(input: &str) -> usize {
     let mut disk = parse(input);
-    disk.defrag();
+    disk.compact_blocks();
     disk.checksum()
 }
 
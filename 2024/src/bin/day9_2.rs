@@ -9,7 +9,7 @@ pub fn main() {
 
 fn compute(input: &str) -> usize {
     let mut disk = parse(input);
-    disk.filewise_defrag();
+    disk.compact_files();
     disk.checksum()
 }
 
@@ -17,12 +17,55 @@ fn compute(input: &str) -> usize {
 mod test {
     use crate::compute;
 
-    #[ignore = "problem not solved"]
+    /// Whole-file compaction done one block at a time, rescanning for every
+    /// file. Slow but hard to get wrong.
+    fn reference(input: &str) -> usize {
+        let mut blocks = input.chars()
+            .filter_map(|c| c.to_digit(10))
+            .enumerate()
+            .flat_map(|(idx, len)| std::iter::repeat_n((idx % 2 == 0).then_some(idx / 2), len as usize))
+            .collect::<Vec<_>>();
+
+        let max_id = blocks.iter().flatten().copied().max().unwrap_or(0);
+        for id in (0..=max_id).rev() {
+            let Some(start) = blocks.iter().position(|block| *block == Some(id)) else { continue };
+            let len = blocks[start..].iter().take_while(|block| **block == Some(id)).count();
+
+            let span = (0..start).find(|&at| blocks[at..at + len].iter().all(Option::is_none));
+            if let Some(at) = span {
+                blocks[start..start + len].fill(None);
+                blocks[at..at + len].fill(Some(id));
+            }
+        }
+
+        blocks.iter().enumerate().map(|(idx, block)| idx * block.unwrap_or(0)).sum()
+    }
+
     #[test]
     fn verify_not_broken() {
         let input = include_str!("../../data/day9.txt");
         let res = compute(input);
-        assert_eq!(res, 6448989155953);
+        assert_eq!(res, reference(input));
+    }
+
+    #[test]
+    fn test_reference_sample() {
+        assert_eq!(reference("2333133121414131402"), 2858);
+    }
+
+    #[test]
+    fn test_matches_reference() {
+        for input in [
+            "2333133121414131402",
+            "12345",
+            "1909",
+            "90909",
+            "1313165",
+            "354631466260",
+            "171010402",
+        ] {
+            assert_eq!(compute(input), reference(input), "{input}");
+        }
     }
 
     #[test]
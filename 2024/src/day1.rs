@@ -1,29 +1,87 @@
+use std::collections::HashMap;
+
+use rustc_hash::FxBuildHasher;
+
+/// The two columns of location ids, in input order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LocationLists {
+    pub left: Vec<i32>,
+    pub right: Vec<i32>,
+}
+
+/// Sum of the distances between the smallest left and smallest right id,
+/// the second smallest of each, and so on.
+pub fn total_distance(lists: &LocationLists) -> i32 {
+    let mut left = lists.left.clone();
+    let mut right = lists.right.clone();
+    left.sort_unstable();
+    right.sort_unstable();
+
+    left.iter()
+        .zip(&right)
+        .map(|(a, b)| (a - b).abs())
+        .sum()
+}
+
+/// Sum of every left id times the number of times it shows up on the right.
+pub fn similarity_score(lists: &LocationLists) -> i32 {
+    let mut counts = HashMap::with_capacity_and_hasher(lists.right.len(), FxBuildHasher);
+    for id in &lists.right {
+        *counts.entry(id).or_insert(0) += 1;
+    }
+
+    lists.left.iter()
+        .filter_map(|id| counts.get(id).map(|count| count * id))
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parser::parse_lists, similarity_score, total_distance};
+
+    const SAMPLE: &str = r#"3   4
+4   3
+2   5
+1   3
+3   9
+3   3"#;
+
+    #[test]
+    fn test_total_distance() {
+        assert_eq!(total_distance(&parse_lists(SAMPLE)), 11);
+    }
+
+    #[test]
+    fn test_similarity_score() {
+        assert_eq!(similarity_score(&parse_lists(SAMPLE)), 31);
+    }
+}
+
 pub mod parser {
 
-    use nom::{
-        character::complete::{digit1, space1},
-        combinator::map_res,
-        error::{FromExternalError, ParseError},
-        sequence::separated_pair,
-        IResult, Parser,
-    };
-    use std::num::ParseIntError;
+    use aoc_parse::unsigned;
+    use nom::{character::complete::space1, sequence::separated_pair, IResult};
 
-    pub fn parse_nums(input: &str) -> IResult<&str, (i32, i32)> {
-        separated_pair(map_to_int(digit1), space1, map_to_int(digit1))(input)
+    use super::LocationLists;
+
+    /// Both columns of the input, lines that aren't a pair of ids are skipped.
+    pub fn parse_lists(input: &str) -> LocationLists {
+        let (left, right) = input
+            .lines()
+            .flat_map(parse_nums)
+            .map(|(_, pair)| pair)
+            .unzip();
+
+        LocationLists { left, right }
     }
 
-    fn map_to_int<'a, F, E1>(f: F) -> impl FnMut(&'a str) -> IResult<&'a str, i32, E1>
-    where
-        F: Parser<&'a str, &'a str, E1>,
-        E1: ParseError<&'a str> + FromExternalError<&'a str, ParseIntError>,
-    {
-        map_res(f, |digits: &str| digits.parse::<i32>())
+    pub fn parse_nums(input: &str) -> IResult<&str, (i32, i32)> {
+        separated_pair(unsigned, space1, unsigned)(input)
     }
 
     #[cfg(test)]
     mod test {
-        use super::parse_nums;
+        use super::{parse_lists, parse_nums};
 
         #[test]
         fn test_parse_num_pair() {
@@ -34,5 +92,12 @@ pub mod parser {
             let (_, actual) = actual.unwrap();
             assert_eq!(actual, expected);
         }
+
+        #[test]
+        fn test_parse_lists() {
+            let actual = parse_lists("3   4\n4   3\n\n2   5\n");
+            assert_eq!(actual.left, [3, 4, 2]);
+            assert_eq!(actual.right, [4, 3, 5]);
+        }
     }
 }
@@ -1,108 +1,162 @@
-use std::collections::HashSet;
-use std::collections::VecDeque;
-
-pub fn trailhead_ratings(map: &[Vec<u32>]) -> Vec<u32> {
-    let start_nodes: VecDeque<(usize, usize)> = map.into_iter().enumerate()
-        .flat_map(|(y, line)| line.into_iter().enumerate()
-            .filter_map(|(x, value)| if *value == 0 { Some((y, x)) } else { None })
-            .collect::<Vec<_>>()
-        )
-        .collect();
-
-    start_nodes.into_iter().map(|next_start| {
-        let mut visited: HashSet<(usize, usize)> = HashSet::new();
-        count_paths(map, next_start, &mut visited)
-    })
-    .collect::<Vec<u32>>()
-}
+use itertools::Itertools;
 
-fn count_paths(map: &[Vec<u32>], node: (usize, usize), visited: &mut HashSet<(usize, usize)>) -> u32 {
-    let node_value = map[node.0][node.1];
+/// The peaks a cell can reach, one bit per height 9 cell numbered in row
+/// major order. Only grows as far as the highest peak it holds.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PeakSet(Vec<u64>);
 
-    if node_value == 9 {
-        return 1;
+impl PeakSet {
+    fn insert(&mut self, peak: usize) {
+        let (word, bit) = (peak / 64, peak % 64);
+        if self.0.len() <= word {
+            self.0.resize(word + 1, 0);
+        }
+        self.0[word] |= 1 << bit;
     }
 
-    let mut path_count = 0;
-    visited.insert(node);
-
-    if node.0 > 0 && map[node.0 - 1][node.1].checked_sub(node_value) == Some(1) && !visited.contains(&(node.0 - 1, node.1)) {
-        path_count += count_paths(map, (node.0 - 1, node.1), visited)
+    fn union_with(&mut self, other: &PeakSet) {
+        if self.0.len() < other.0.len() {
+            self.0.resize(other.0.len(), 0);
+        }
+        for (word, other) in self.0.iter_mut().zip(&other.0) {
+            *word |= other;
+        }
     }
 
-    if node.0 < map.len() - 1 && map[node.0 + 1][node.1].checked_sub(node_value) == Some(1) && !visited.contains(&(node.0 + 1, node.1)) {
-        path_count += count_paths(map, (node.0 + 1, node.1), visited)
+    pub fn contains(&self, peak: usize) -> bool {
+        self.0.get(peak / 64).is_some_and(|word| word & (1 << (peak % 64)) != 0)
     }
 
-    if node.1 > 0 && map[node.0][node.1 - 1].checked_sub(node_value) == Some(1) && !visited.contains(&(node.0, node.1 - 1)) {
-        path_count += count_paths(map, (node.0, node.1 - 1), visited)
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
     }
 
-    if node.1 < map[node.0].len() - 1 && map[node.0][node.1 + 1].checked_sub(node_value) == Some(1) && !visited.contains(&(node.0, node.1 + 1)) {
-        path_count += count_paths(map, (node.0, node.1 + 1), visited)
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
     }
+}
 
-    visited.remove(&node);
-    path_count
+/// For every cell of a map, how many distinct hiking trails lead from it to
+/// a peak and which peaks those are.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trails {
+    width: usize,
+    height: usize,
+    heights: Vec<Option<u32>>,
+    paths: Vec<u64>,
+    peaks: Vec<PeakSet>,
 }
 
-pub fn trailhead_scores(map: &[Vec<u32>]) -> Vec<u32> {
-    let start_nodes: VecDeque<(usize, usize)> = map.into_iter().enumerate()
-        .flat_map(|(y, line)| line.into_iter().enumerate()
-            .filter_map(|(x, value)| if *value == 0 { Some((y, x)) } else { None })
-            .collect::<Vec<_>>()
-        )
-        .collect();
-
-    start_nodes.into_iter().map(|next_start| {
-            let mut count = 0;
-            let mut visited: HashSet<(usize, usize)> = HashSet::new();
-            let mut queue: VecDeque<(usize, usize)> = VecDeque::from([
-                next_start
-            ]);
-
-            while let Some(node) = queue.pop_front() {
-                let node_value = map[node.0][node.1];
-                if visited.contains(&node) {
-                    continue;
+impl Trails {
+    /// Works down from the peaks one height at a time, so every cell only
+    /// looks at its neighbours once: a cell's paths and peaks are the sums
+    /// and unions of its neighbours one step higher.
+    pub fn new(map: &[Vec<u32>]) -> Self {
+        let width = map.iter().map(Vec::len).max().unwrap_or(0);
+        let height = map.len();
+
+        let mut heights = vec![None; width * height];
+        let mut by_height = vec![Vec::new(); 10];
+        for (y, line) in map.iter().enumerate() {
+            for (x, &value) in line.iter().enumerate() {
+                heights[y * width + x] = Some(value);
+                if let Some(cells) = by_height.get_mut(value as usize) {
+                    cells.push(y * width + x);
                 }
+            }
+        }
 
-                visited.insert(node);
+        let mut trails = Trails {
+            width,
+            height,
+            heights,
+            paths: vec![0; width * height],
+            peaks: vec![PeakSet::default(); width * height],
+        };
 
-                if node_value == 9 {
-                    count += 1;
-                    continue;
-                }
+        for (peak, &cell) in by_height[9].iter().enumerate() {
+            trails.paths[cell] = 1;
+            trails.peaks[cell].insert(peak);
+        }
 
-                if node.0 > 0 && map[node.0 - 1][node.1].checked_sub(node_value) == Some(1) {
-                    queue.push_back((node.0 - 1, node.1));
+        for value in (0..9).rev() {
+            for &cell in &by_height[value as usize] {
+                let mut peaks = std::mem::take(&mut trails.peaks[cell]);
+                for next in trails.neighbours(cell).filter(|&next| trails.heights[next] == Some(value + 1)) {
+                    trails.paths[cell] += trails.paths[next];
+                    peaks.union_with(&trails.peaks[next]);
                 }
+                trails.peaks[cell] = peaks;
+            }
+        }
 
-                if node.0 < map.len() - 1 && map[node.0 + 1][node.1].checked_sub(node_value) == Some(1) {
-                    queue.push_back((node.0 + 1, node.1));
-                }
+        trails
+    }
 
-                if node.1 > 0 && map[node.0][node.1 - 1].checked_sub(node_value) == Some(1) {
-                    queue.push_back((node.0, node.1 - 1));
-                }
+    fn neighbours(&self, cell: usize) -> impl Iterator<Item = usize> {
+        let (y, x) = (cell / self.width, cell % self.width);
+        let width = self.width;
 
-                if node.1 < map[node.0].len() - 1 && map[node.0][node.1 + 1].checked_sub(node_value) == Some(1) {
-                    queue.push_back((node.0, node.1 + 1));
-                }
-            }
+        [
+            (y > 0).then(|| cell - width),
+            (y + 1 < self.height).then(|| cell + width),
+            (x > 0).then(|| cell - 1),
+            (x + 1 < width).then(|| cell + 1),
+        ]
+        .into_iter()
+        .flatten()
+    }
 
-            count
-        }
-    )
-    .collect()
+    /// Every height 0 cell as `(y, x)`, in row major order.
+    pub fn trailheads(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.heights.iter()
+            .enumerate()
+            .filter(|(_, value)| **value == Some(0))
+            .map(|(cell, _)| (cell / self.width, cell % self.width))
+    }
+
+    /// Number of distinct peaks reachable from `(y, x)`.
+    pub fn score(&self, (y, x): (usize, usize)) -> usize {
+        self.peaks[y * self.width + x].len()
+    }
+
+    /// Number of distinct trails from `(y, x)` to any peak.
+    pub fn rating(&self, (y, x): (usize, usize)) -> u64 {
+        self.paths[y * self.width + x]
+    }
+
+    pub fn peaks(&self, (y, x): (usize, usize)) -> &PeakSet {
+        &self.peaks[y * self.width + x]
+    }
+
+    pub fn path_counts(&self) -> Vec<Vec<u64>> {
+        self.paths.chunks(self.width.max(1)).map(<[u64]>::to_vec).collect()
+    }
+
+    /// Path counts of every cell as comma separated rows.
+    pub fn heatmap(&self) -> String {
+        self.path_counts()
+            .into_iter()
+            .map(|row| row.iter().join(",") + "\n")
+            .collect()
+    }
+}
 
+pub fn trailhead_ratings(map: &[Vec<u32>]) -> Vec<u64> {
+    let trails = Trails::new(map);
+    trails.trailheads().map(|trailhead| trails.rating(trailhead)).collect()
+}
+
+pub fn trailhead_scores(map: &[Vec<u32>]) -> Vec<usize> {
+    let trails = Trails::new(map);
+    trails.trailheads().map(|trailhead| trails.score(trailhead)).collect()
 }
 
 #[cfg(test)]
 mod test {
     use crate::day10::trailhead_ratings;
 
-    use super::{trailhead_scores, parser::parse};
+    use super::{trailhead_scores, parser::parse, Trails};
 
     #[test]
     fn test_sample_input_pt2() {
@@ -140,7 +194,57 @@ mod test {
         println!("{trailhead_scores:?}");
         assert_eq!(trailhead_scores.len(), 9);
         assert_eq!(trailhead_scores, vec![5, 6, 5, 3, 1, 3, 5, 3, 5]);
-        assert_eq!(36u32, trailhead_scores.into_iter().sum());
+        assert_eq!(36usize, trailhead_scores.into_iter().sum());
+    }
+
+    #[test]
+    fn test_heatmap() {
+        // height is x + y, so the path counts from each cell to the one peak
+        // in the corner are binomial coefficients
+        let input = r#"012345
+123456
+234567
+345678
+456789"#;
+
+        let trails = Trails::new(&parse(input));
+        assert_eq!(trails.rating((0, 0)), 126);
+        assert_eq!(trails.score((0, 0)), 1);
+        assert_eq!(trails.heatmap(), r#"126,70,35,15,5,1
+56,35,20,10,4,1
+21,15,10,6,3,1
+6,5,4,3,2,1
+1,1,1,1,1,1
+"#);
+    }
+
+    #[test]
+    fn test_peaks() {
+        let input = r#"9876789
+1111111"#;
+
+        let trails = Trails::new(&parse(input));
+        assert_eq!(trails.trailheads().count(), 0);
+
+        let peaks = trails.peaks((0, 3));
+        assert_eq!(peaks.len(), 2);
+        assert!(peaks.contains(0) && peaks.contains(1));
+        assert_eq!(trails.rating((0, 3)), 2);
+        assert!(trails.peaks((1, 3)).is_empty());
+    }
+
+    #[test]
+    fn test_many_peaks() {
+        // 100 peaks, more than fit in a single word of the bitset
+        let input = [
+            "9".repeat(100),
+            "8".repeat(100),
+        ].join("\n");
+
+        let trails = Trails::new(&parse(&input));
+        assert_eq!(trails.peaks((1, 0)).len(), 1);
+        assert!(trails.peaks((1, 99)).contains(99));
+        assert_eq!(trails.score((0, 70)), 1);
     }
 }
 
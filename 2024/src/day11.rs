@@ -1,5 +1,108 @@
 use std::collections::HashMap;
 
+/// The left and right halves of a number with an even number of digits,
+/// e.g. `1000` into `10` and `0`.
+pub fn split_digits(value: u64) -> Option<(u64, u64)> {
+    let digits = value.checked_ilog10()? + 1;
+    if digits % 2 != 0 {
+        return None;
+    }
+
+    let half = 10u64.pow(digits / 2);
+    Some((value / half, value % half))
+}
+
+/// What a single stone turns into after one blink.
+pub fn evolve(value: u64) -> (u64, Option<u64>) {
+    if value == 0 {
+        return (1, None);
+    }
+
+    match split_digits(value) {
+        Some((left, right)) => (left, Some(right)),
+        None => (value * 2024, None),
+    }
+}
+
+/// A line of stones as a count per engraved value. The order of the stones
+/// never matters, so stones with the same value only have to be evolved
+/// once per blink.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoneLine {
+    counts: HashMap<u64, usize>,
+    blinks: usize,
+}
+
+impl StoneLine {
+    pub fn new(stones: &[u64]) -> Self {
+        let mut counts = HashMap::new();
+        for &stone in stones {
+            *counts.entry(stone).or_default() += 1;
+        }
+
+        StoneLine { counts, blinks: 0 }
+    }
+
+    pub fn blink(&mut self) {
+        let mut next = HashMap::with_capacity(self.counts.len());
+        for (&value, &count) in &self.counts {
+            let (left, right) = evolve(value);
+            *next.entry(left).or_default() += count;
+            if let Some(right) = right {
+                *next.entry(right).or_default() += count;
+            }
+        }
+
+        self.counts = next;
+        self.blinks += 1;
+    }
+
+    /// The line after `blinks` more blinks.
+    pub fn after(mut self, blinks: usize) -> Self {
+        for _ in 0..blinks {
+            self.blink();
+        }
+        self
+    }
+
+    /// This line followed by the line after every further blink, forever.
+    pub fn generations(self) -> impl Iterator<Item = StoneLine> {
+        std::iter::successors(Some(self), |line| {
+            let mut next = line.clone();
+            next.blink();
+            Some(next)
+        })
+    }
+
+    /// How many times it has blinked since [`StoneLine::new`].
+    pub fn blinks(&self) -> usize {
+        self.blinks
+    }
+
+    /// Total number of stones.
+    pub fn len(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Number of different values engraved on the stones.
+    pub fn distinct(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Number of stones engraved with `value`.
+    pub fn count(&self, value: u64) -> usize {
+        self.counts.get(&value).copied().unwrap_or(0)
+    }
+
+    pub fn counts(&self) -> &HashMap<u64, usize> {
+        &self.counts
+    }
+}
+
 #[derive(Hash, Eq, PartialEq)]
 struct CacheKey {
     stone_value: u64,
@@ -97,7 +200,59 @@ pub mod parser {
 
 #[cfg(test)]
 mod test {
-    use crate::day11::{blink, memoized_blink};
+    use test_case::test_case;
+
+    use crate::day11::{blink, memoized_blink, split_digits, StoneLine};
+
+    #[test_case(0, None)]
+    #[test_case(7, None)]
+    #[test_case(10, Some((1, 0)))]
+    #[test_case(1000, Some((10, 0)))]
+    #[test_case(253000, Some((253, 0)))]
+    #[test_case(99999, None)]
+    #[test_case(u64::MAX, Some((1844674407, 3709551615)))]
+    fn test_split_digits(value: u64, expected: Option<(u64, u64)>) {
+        assert_eq!(split_digits(value), expected);
+    }
+
+    #[test]
+    fn test_stone_line_generations() {
+        // the lengths from the puzzle's walkthrough of `125 17`
+        let lengths = StoneLine::new(&[125, 17])
+            .generations()
+            .take(7)
+            .map(|line| line.len())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lengths, [2, 3, 4, 5, 9, 13, 22]);
+    }
+
+    #[test]
+    fn test_stone_line_counts() {
+        // 2097446912 14168 4048 2 0 2 4 40 48 2024 40 48 80 96 2 8 6 7 6 0 3 2
+        let line = StoneLine::new(&[125, 17]).after(6);
+
+        assert_eq!(line.blinks(), 6);
+        assert_eq!(line.count(2), 4);
+        assert_eq!(line.count(40), 2);
+        assert_eq!(line.count(5), 0);
+        assert_eq!(line.distinct(), 15);
+    }
+
+    #[test]
+    fn test_stone_line_matches_recursion() {
+        for stones in [vec![0], vec![125, 17], vec![1, 2024, 9, 1000]] {
+            for blinks in 0..15 {
+                let recursive = stones.iter().map(|&stone| blink(stone, 0, blinks)).sum::<usize>();
+                assert_eq!(StoneLine::new(&stones).after(blinks).len(), recursive, "{stones:?} after {blinks}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_stone_line_sample() {
+        assert_eq!(StoneLine::new(&[125, 17]).after(25).len(), 55312);
+    }
 
     #[test]
     fn test_sample_input() {
@@ -0,0 +1,269 @@
+use std::collections::VecDeque;
+
+/// The garden map, one plant type per plot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Garden {
+    width: usize,
+    height: usize,
+    plots: Vec<u8>,
+}
+
+/// A group of touching plots with the same plant type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub plant: char,
+    /// Every plot in the region as `(y, x)`, in row major order.
+    pub plots: Vec<(usize, usize)>,
+    pub perimeter: usize,
+    pub sides: usize,
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.plots.len()
+    }
+
+    pub fn price(&self) -> usize {
+        self.area() * self.perimeter
+    }
+
+    pub fn bulk_price(&self) -> usize {
+        self.area() * self.sides
+    }
+}
+
+impl Garden {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn plant(&self, y: isize, x: isize) -> Option<u8> {
+        if y < 0 || x < 0 || y as usize >= self.height || x as usize >= self.width {
+            return None;
+        }
+
+        Some(self.plots[y as usize * self.width + x as usize])
+    }
+
+    /// The region each plot belongs to as an index into [`Garden::regions`],
+    /// in row major order.
+    pub fn labels(&self) -> Vec<usize> {
+        let mut labels = vec![usize::MAX; self.plots.len()];
+        let mut next = 0;
+
+        for start in 0..self.plots.len() {
+            if labels[start] != usize::MAX {
+                continue;
+            }
+
+            labels[start] = next;
+            let mut queue = VecDeque::from([start]);
+            while let Some(plot) = queue.pop_front() {
+                for neighbour in self.neighbours(plot) {
+                    if labels[neighbour] == usize::MAX && self.plots[neighbour] == self.plots[start] {
+                        labels[neighbour] = next;
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+
+            next += 1;
+        }
+
+        labels
+    }
+
+    fn neighbours(&self, plot: usize) -> impl Iterator<Item = usize> {
+        let (y, x) = (plot / self.width, plot % self.width);
+        let width = self.width;
+
+        [
+            (y > 0).then(|| plot - width),
+            (y + 1 < self.height).then(|| plot + width),
+            (x > 0).then(|| plot - 1),
+            (x + 1 < width).then(|| plot + 1),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Every region, ordered by the first plot of it in row major order.
+    ///
+    /// Each edge of a plot that doesn't face the same plant is perimeter.
+    /// A region has as many sides as it has corners, and a plot is on a
+    /// corner wherever both of two adjacent edges are perimeter (outer
+    /// corner) or neither is but the diagonal between them is (inner corner).
+    pub fn regions(&self) -> Vec<Region> {
+        let labels = self.labels();
+        let count = labels.iter().max().map_or(0, |max| max + 1);
+        let mut regions = vec![None; count];
+
+        for (plot, &label) in labels.iter().enumerate() {
+            let plant = self.plots[plot];
+            let region = regions[label].get_or_insert_with(|| Region {
+                plant: plant as char,
+                plots: Vec::new(),
+                perimeter: 0,
+                sides: 0,
+            });
+
+            let (y, x) = (plot / self.width, plot % self.width);
+            region.plots.push((y, x));
+
+            let same = |dy: isize, dx: isize| self.plant(y as isize + dy, x as isize + dx) == Some(plant);
+            let edges = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+            region.perimeter += edges.iter().filter(|(dy, dx)| !same(*dy, *dx)).count();
+
+            // each edge with the one clockwise of it
+            region.sides += edges.iter()
+                .zip(edges.iter().cycle().skip(1))
+                .filter(|((ay, ax), (by, bx))| {
+                    let (a, b) = (same(*ay, *ax), same(*by, *bx));
+                    (!a && !b) || (a && b && !same(ay + by, ax + bx))
+                })
+                .count();
+        }
+
+        regions.into_iter().flatten().collect()
+    }
+
+    /// Sum of area times perimeter of every region.
+    pub fn total_price(&self) -> usize {
+        self.regions().iter().map(Region::price).sum()
+    }
+
+    /// Sum of area times number of sides of every region.
+    pub fn total_bulk_price(&self) -> usize {
+        self.regions().iter().map(Region::bulk_price).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::parser::parse;
+
+    pub const SMALL: &str = r#"AAAA
+BBCD
+BBCC
+EEEC"#;
+
+    const ENCLAVES: &str = r#"OOOOO
+OXOXO
+OOOOO
+OXOXO
+OOOOO"#;
+
+    const LARGE: &str = r#"RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE"#;
+
+    const E_SHAPE: &str = r#"EEEEE
+EXXXX
+EEEEE
+EXXXX
+EEEEE"#;
+
+    const DIAGONAL: &str = r#"AAAAAA
+AAABBA
+AAABBA
+ABBAAA
+ABBAAA
+AAAAAA"#;
+
+    #[test]
+    fn test_small_regions() {
+        let regions = parse(SMALL).regions();
+        let summary = regions.iter()
+            .map(|region| (region.plant, region.area(), region.perimeter, region.sides))
+            .collect::<Vec<_>>();
+
+        assert_eq!(summary, [
+            ('A', 4, 10, 4),
+            ('B', 4, 8, 4),
+            ('C', 4, 10, 8),
+            ('D', 1, 4, 4),
+            ('E', 3, 8, 4),
+        ]);
+    }
+
+    #[test]
+    fn test_plots_in_row_major_order() {
+        let regions = parse(LARGE).regions();
+
+        assert_eq!(regions[0].plots[..5], [(0, 0), (0, 1), (0, 2), (0, 3), (1, 0)]);
+        assert!(regions.iter().all(|region| region.plots.is_sorted()));
+    }
+
+    #[test]
+    fn test_same_plant_separate_regions() {
+        let garden = parse(ENCLAVES);
+        let regions = garden.regions();
+
+        assert_eq!(regions.len(), 5);
+        assert_eq!(regions.iter().filter(|region| region.plant == 'X').count(), 4);
+        assert_eq!(regions[0].perimeter, 36);
+        assert_eq!(regions[0].sides, 20);
+
+        let labels = garden.labels();
+        assert_ne!(labels[6], labels[8]);
+    }
+
+    #[test_case(SMALL, 140)]
+    #[test_case(ENCLAVES, 772)]
+    #[test_case(LARGE, 1930)]
+    fn test_total_price(input: &str, expected: usize) {
+        assert_eq!(parse(input).total_price(), expected);
+    }
+
+    #[test_case(SMALL, 80)]
+    #[test_case(ENCLAVES, 436)]
+    #[test_case(E_SHAPE, 236)]
+    #[test_case(DIAGONAL, 368)]
+    #[test_case(LARGE, 1206)]
+    fn test_total_bulk_price(input: &str, expected: usize) {
+        assert_eq!(parse(input).total_bulk_price(), expected);
+    }
+}
+
+pub mod parser {
+    use super::Garden;
+
+    pub fn parse(input: &str) -> Garden {
+        let lines = input.trim().lines().map(str::trim).collect::<Vec<_>>();
+        let width = lines.first().map_or(0, |line| line.len());
+        assert!(lines.iter().all(|line| line.len() == width), "the garden should be rectangular");
+
+        Garden {
+            width,
+            height: lines.len(),
+            plots: lines.into_iter().flat_map(str::bytes).collect(),
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::parse;
+        use crate::day12::test::SMALL;
+
+        #[test]
+        fn test_parse() {
+            let garden = parse(SMALL);
+            assert_eq!((garden.width(), garden.height()), (4, 4));
+            assert_eq!(garden.plots[4..8], *b"BBCD");
+        }
+    }
+}
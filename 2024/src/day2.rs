@@ -1,14 +1,243 @@
-use std::cmp::Ordering;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Increasing,
+    Decreasing,
+}
+
+impl Trend {
+    fn of(from: i32, to: i32) -> Option<Trend> {
+        match to.cmp(&from) {
+            std::cmp::Ordering::Less => Some(Trend::Decreasing),
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(Trend::Increasing),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// The level is the same as the one before it.
+    Equal,
+    /// The level moves further from the one before it than allowed.
+    StepTooLarge,
+    /// The level goes the other way from the first two levels.
+    DirectionChange,
+}
+
+/// The first level that breaks a rule, compared to the level before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fault {
+    pub index: usize,
+    pub reason: Violation,
+}
+
+/// How far apart neighbouring levels may be, and how many levels the
+/// problem dampener may remove.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafetyRules {
+    pub max_step: i32,
+    pub max_removals: usize,
+}
+
+impl SafetyRules {
+    pub const PART_1: SafetyRules = SafetyRules { max_step: 3, max_removals: 0 };
+    pub const PART_2: SafetyRules = SafetyRules { max_step: 3, max_removals: 1 };
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum ReportStatus {
-    Safe(Ordering),
-    Unsafe,
+pub struct ReportVerdict {
+    /// Which way a safe report goes once `removals` are taken out, or for an
+    /// unsafe one, which way its first two levels go. `None` if there's no
+    /// direction to speak of.
+    pub trend: Option<Trend>,
+    /// Where the report goes wrong as it is, `None` if it's already safe.
+    pub fault: Option<Fault>,
+    /// The indices of the levels to remove to make it safe, empty if it's
+    /// safe as it is and `None` if it takes more removals than allowed.
+    pub removals: Option<Vec<usize>>,
+}
+
+impl ReportVerdict {
+    pub fn is_safe(&self) -> bool {
+        self.removals.is_some()
+    }
+}
+
+fn first_fault(levels: &[i32], max_step: i32) -> Option<Fault> {
+    let trend = Trend::of(*levels.first()?, *levels.get(1)?);
+
+    levels.windows(2).enumerate().find_map(|(idx, pair)| {
+        let reason = match Trend::of(pair[0], pair[1]) {
+            None => Violation::Equal,
+            step if step != trend => Violation::DirectionChange,
+            _ if (pair[1] - pair[0]).abs() > max_step => Violation::StepTooLarge,
+            _ => return None,
+        };
+
+        Some(Fault { index: idx + 1, reason })
+    })
+}
+
+/// The fewest levels to remove so the rest all go in `trend` by steps of at
+/// most `rules.max_step`, preferring to remove later levels.
+///
+/// `kept[i]` holds the fewest removals that make the levels up to and
+/// including `i` safe with `i` kept, along with the kept level before it.
+/// Only the last `max_removals + 1` levels can come before `i`, so this is
+/// linear in the length of the report.
+fn fewest_removals(levels: &[i32], trend: Trend, rules: SafetyRules) -> Option<Vec<usize>> {
+    let allowed = rules.max_removals;
+    let step_ok = |from: i32, to: i32| {
+        Trend::of(from, to) == Some(trend) && (to - from).abs() <= rules.max_step
+    };
+
+    let mut kept: Vec<Option<(usize, Option<usize>)>> = Vec::with_capacity(levels.len());
+    for idx in 0..levels.len() {
+        // every level before this one removed
+        let mut best = (idx <= allowed).then_some((idx, None));
+
+        for prev in idx.saturating_sub(allowed + 1)..idx {
+            let Some((removed, _)) = kept[prev] else { continue };
+            let removed = removed + idx - prev - 1;
+            if step_ok(levels[prev], levels[idx]) && best.is_none_or(|(fewest, _)| removed < fewest) {
+                best = Some((removed, Some(prev)));
+            }
+        }
+
+        kept.push(best);
+    }
+
+    let (removed, mut last) = (levels.len().saturating_sub(allowed + 1)..levels.len())
+        .filter_map(|last| kept[last].map(|(removed, _)| (removed + levels.len() - last - 1, Some(last))))
+        .min_by_key(|(removed, _)| *removed)
+        .unwrap_or((levels.len(), None));
+
+    if removed > allowed {
+        return None;
+    }
+
+    let mut keep = vec![false; levels.len()];
+    while let Some(idx) = last {
+        keep[idx] = true;
+        last = kept[idx].and_then(|(_, prev)| prev);
+    }
+
+    Some((0..levels.len()).filter(|&idx| !keep[idx]).collect())
+}
+
+/// Checks a report, trying both directions when levels may be removed.
+pub fn check_report(levels: &[i32], rules: SafetyRules) -> ReportVerdict {
+    let initial = match levels {
+        [first, second, ..] => Trend::of(*first, *second),
+        _ => None,
+    };
+
+    let fault = first_fault(levels, rules.max_step);
+    if fault.is_none() {
+        return ReportVerdict { trend: initial, fault, removals: Some(Vec::new()) };
+    }
+
+    // the direction the report starts in goes first, so it wins a tie
+    let trends = match initial {
+        Some(Trend::Decreasing) => [Trend::Decreasing, Trend::Increasing],
+        _ => [Trend::Increasing, Trend::Decreasing],
+    };
+
+    let fixed = trends.into_iter()
+        .filter_map(|trend| fewest_removals(levels, trend, rules).map(|removals| (trend, removals)))
+        .min_by_key(|(_, removals)| removals.len());
+
+    match fixed {
+        Some((trend, removals)) => ReportVerdict {
+            trend: (levels.len() - removals.len() >= 2).then_some(trend),
+            fault,
+            removals: Some(removals),
+        },
+        None => ReportVerdict { trend: initial, fault, removals: None },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::{check_report, Fault, ReportVerdict, SafetyRules, Trend, Violation};
+
+    #[test_case(&[7, 6, 4, 2, 1], Some(Trend::Decreasing), None, Some(vec![]))]
+    #[test_case(&[1, 2, 7, 8, 9], Some(Trend::Increasing), Some((2, Violation::StepTooLarge)), None)]
+    #[test_case(&[9, 7, 6, 2, 1], Some(Trend::Decreasing), Some((3, Violation::StepTooLarge)), None)]
+    #[test_case(&[1, 3, 2, 4, 5], Some(Trend::Increasing), Some((2, Violation::DirectionChange)), Some(vec![2]))]
+    #[test_case(&[8, 6, 4, 4, 1], Some(Trend::Decreasing), Some((3, Violation::Equal)), Some(vec![3]))]
+    #[test_case(&[1, 3, 6, 7, 9], Some(Trend::Increasing), None, Some(vec![]))]
+    #[test_case(&[9, 5, 7, 8, 9], Some(Trend::Increasing), Some((1, Violation::StepTooLarge)), Some(vec![0]))]
+    #[test_case(&[1, 1, 2, 3], Some(Trend::Increasing), Some((1, Violation::Equal)), Some(vec![1]))]
+    #[test_case(&[5, 5], None, Some((1, Violation::Equal)), Some(vec![1]))]
+    #[test_case(&[4], None, None, Some(vec![]))]
+    fn test_sample_verdicts(
+        levels: &[i32],
+        trend: Option<Trend>,
+        fault: Option<(usize, Violation)>,
+        removals: Option<Vec<usize>>,
+    ) {
+        let expected = ReportVerdict {
+            trend,
+            fault: fault.map(|(index, reason)| Fault { index, reason }),
+            removals,
+        };
+        assert_eq!(check_report(levels, SafetyRules::PART_2), expected);
+    }
+
+    #[test]
+    fn test_configurable_rules() {
+        let levels = [1, 5, 2, 3, 9, 4];
+
+        assert!(!check_report(&levels, SafetyRules::PART_2).is_safe());
+        assert_eq!(
+            check_report(&levels, SafetyRules { max_step: 3, max_removals: 2 }).removals,
+            Some(vec![1, 4])
+        );
+
+        let levels = [1, 6, 11];
+
+        assert!(!check_report(&levels, SafetyRules::PART_1).is_safe());
+        assert_eq!(
+            check_report(&levels, SafetyRules { max_step: 5, max_removals: 0 }).removals,
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        fn safe(levels: &[i32]) -> bool {
+            check_report(levels, SafetyRules::PART_1).is_safe()
+        }
+
+        let mut seed = 7u32;
+        for _ in 0..2000 {
+            let levels = (0..6)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                    (seed >> 16) as i32 % 8
+                })
+                .collect::<Vec<_>>();
+
+            let verdict = check_report(&levels, SafetyRules::PART_2);
+            let brute = safe(&levels) || (0..levels.len()).any(|skip| {
+                let rest = levels.iter().enumerate().filter(|(i, _)| *i != skip).map(|(_, v)| *v).collect::<Vec<_>>();
+                safe(&rest)
+            });
+
+            assert_eq!(verdict.is_safe(), brute, "{levels:?}");
+            if let Some(removals) = verdict.removals {
+                let rest = levels.iter().enumerate().filter(|(i, _)| !removals.contains(i)).map(|(_, v)| *v).collect::<Vec<_>>();
+                assert!(safe(&rest), "{levels:?} without {removals:?}");
+            }
+        }
+    }
 }
 
 pub mod parser {
-    use crate::day2::ReportStatus;
-    use itertools::Itertools;
+    use crate::day2::{check_report, SafetyRules};
     use nom::{
         character::complete::{digit1, space1},
         combinator::{map, map_res},
@@ -16,49 +245,13 @@ pub mod parser {
         IResult,
     };
 
-    pub fn parse_line_safety(input: &str, enable_damper: bool) -> IResult<&str, bool> {
-        map(
-            separated_list1(space1, map_res(digit1, |d: &str| d.parse::<i32>())),
-            |list| {
-                if is_line_safe(&list) {
-                    true
-                } else if enable_damper {
-                    for idx in 0..list.len() {
-                        let without_idx: Vec<_> = list
-                            .iter()
-                            .enumerate()
-                            .filter(|&(i, _)| i != idx)
-                            .map(|(_, &v)| v)
-                            .collect();
-
-                        let is_safe = is_line_safe(&without_idx);
-                        if is_safe {
-                            return true;
-                        }
-                    }
-                    false
-                } else {
-                    false
-                }
-            },
-        )(input)
+    pub fn parse_report(input: &str) -> IResult<&str, Vec<i32>> {
+        separated_list1(space1, map_res(digit1, |d: &str| d.parse::<i32>()))(input)
     }
 
-    fn is_line_safe(list: &[i32]) -> bool {
-        list.into_iter()
-            .tuple_windows::<(_, _)>()
-            .map(|(a, b)| {
-                if a == b || (a - b).abs() > 3 {
-                    ReportStatus::Unsafe
-                } else {
-                    ReportStatus::Safe(a.cmp(&b))
-                }
-            })
-            .reduce(|acc, next| match (acc, next) {
-                (ReportStatus::Safe(a), ReportStatus::Safe(b)) if a == b => ReportStatus::Safe(a),
-                _ => ReportStatus::Unsafe,
-            })
-            != Some(ReportStatus::Unsafe)
+    pub fn parse_line_safety(input: &str, enable_damper: bool) -> IResult<&str, bool> {
+        let rules = if enable_damper { SafetyRules::PART_2 } else { SafetyRules::PART_1 };
+        map(parse_report, move |levels| check_report(&levels, rules).is_safe())(input)
     }
 
     #[cfg(test)]
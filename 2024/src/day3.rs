@@ -1,137 +1,374 @@
-pub mod parser {
-    use nom::{
-        branch::alt,
-        bytes::complete::{tag, take},
-        character::complete::digit1,
-        combinator::{map, map_res, value},
-        multi::{fold_many1, many1},
-        sequence::{delimited, separated_pair},
-        IResult,
-    };
+use std::ops::{Deref, Range};
+
+/// Most arguments an instruction can take.
+pub const MAX_ARGS: usize = 4;
+
+/// The numeric arguments of an instruction, without allocating.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Args {
+    values: [i64; MAX_ARGS],
+    len: usize,
+}
+
+impl Args {
+    fn push(&mut self, value: i64) -> Option<()> {
+        *self.values.get_mut(self.len)? = value;
+        self.len += 1;
+        Some(())
+    }
+}
+
+impl Deref for Args {
+    type Target = [i64];
+
+    fn deref(&self) -> &[i64] {
+        &self.values[..self.len]
+    }
+}
 
-    #[derive(Debug, Clone, Eq, PartialEq)]
-    enum Conditional {
-        Do,
-        Dont,
+impl<const N: usize> From<[i64; N]> for Args {
+    fn from(values: [i64; N]) -> Self {
+        let mut args = Args::default();
+        for value in values {
+            args.push(value).expect("too many arguments");
+        }
+        args
     }
+}
+
+/// An instruction found in corrupted memory, e.g. `mul(2,4)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub name: &'static str,
+    pub args: Args,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<T> {
+    /// Byte offsets of `item` in the scanned input.
+    pub span: Range<usize>,
+    pub item: T,
+}
 
-    #[derive(Debug, Clone, Eq, PartialEq)]
-    enum Instruction {
-        Conditional(Conditional),
-        Mul((i64, i64)),
-        Noop,
+/// The instructions the scanner looks for, by name and number of arguments.
+/// Anything else in the input is corruption.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstructionTable {
+    entries: Vec<(&'static str, usize)>,
+}
+
+impl InstructionTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, name: &'static str, arity: usize) -> Self {
+        assert!(!name.is_empty(), "instructions need a name");
+        assert!(arity <= MAX_ARGS, "{name} takes more than {MAX_ARGS} arguments");
+        self.entries.push((name, arity));
+        self
     }
 
-    fn chomp_and_parse<T, F>(input: &str, mut f: F) -> IResult<&str, Vec<T>>
-    where
-        F: FnMut(&str) -> IResult<&str, T>,
-    {
-        let mut items: Vec<T> = Vec::with_capacity(1024);
-        let mut input = input;
+    /// `mul(X,Y)`, `do()` and `don't()`.
+    pub fn corrupted_memory() -> Self {
+        Self::new().with("mul", 2).with("do", 0).with("don't", 0)
+    }
 
-        while input.len() > 0 {
-            if let Ok((rem, item)) = f(input) {
-                items.push(item);
-                input = rem;
-            } else {
-                input = &input[1..];
+    /// The instruction starting right at the beginning of `input`, if any,
+    /// and its length in bytes.
+    fn instruction_at(&self, input: &str) -> Option<(usize, Instruction)> {
+        self.entries.iter().find_map(|&(name, arity)| {
+            let (rest, args) = parser::call(name, arity)(input).ok()?;
+            Some((input.len() - rest.len(), Instruction { name, args }))
+        })
+    }
+
+    /// Every distinct byte an instruction can start with.
+    fn first_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.entries.iter().map(|(name, _)| name.as_bytes()[0]).collect::<Vec<_>>();
+        bytes.sort_unstable();
+        bytes.dedup();
+        bytes
+    }
+}
+
+/// Finds the instructions in `input` in order. Only offsets holding the first
+/// byte of some instruction name are tried, and those are found with
+/// `memchr` when there are at most three such bytes.
+pub fn scan<'a>(input: &'a str, table: &'a InstructionTable) -> impl Iterator<Item = Spanned<Instruction>> + 'a {
+    let first_bytes = table.first_bytes();
+    let mut lookup = [false; 256];
+    for &byte in &first_bytes {
+        lookup[byte as usize] = true;
+    }
+
+    let next_candidate = move |haystack: &[u8]| match first_bytes[..] {
+        [] => None,
+        [a] => memchr::memchr(a, haystack),
+        [a, b] => memchr::memchr2(a, b, haystack),
+        [a, b, c] => memchr::memchr3(a, b, c, haystack),
+        _ => haystack.iter().position(|&byte| lookup[byte as usize]),
+    };
+
+    let mut position = 0;
+    std::iter::from_fn(move || {
+        while let Some(offset) = next_candidate(&input.as_bytes()[position..]) {
+            let start = position + offset;
+            match table.instruction_at(&input[start..]) {
+                Some((len, item)) => {
+                    position = start + len;
+                    return Some(Spanned { span: start..position, item });
+                }
+                None => position = start + 1,
+            }
+        }
+
+        position = input.len();
+        None
+    })
+}
+
+/// Same as [`scan`] but tries every byte offset, for checking the fast
+/// path against.
+pub fn scan_bytewise<'a>(input: &'a str, table: &'a InstructionTable) -> impl Iterator<Item = Spanned<Instruction>> + 'a {
+    let mut position = 0;
+    std::iter::from_fn(move || {
+        while position < input.len() {
+            let start = position;
+            if let Some((len, item)) = input.get(start..).and_then(|rest| table.instruction_at(rest)) {
+                position = start + len;
+                return Some(Spanned { span: start..position, item });
             }
+            position += 1;
         }
 
-        Ok((input, items))
+        None
+    })
+}
+
+/// Decides which instructions take effect.
+pub trait Gate {
+    /// Whether `instruction` runs. Sees every instruction in order, including
+    /// the ones that only change the gate.
+    fn allows(&mut self, instruction: &Instruction) -> bool;
+}
+
+/// Lets everything through.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ungated;
+
+impl Gate for Ungated {
+    fn allows(&mut self, _: &Instruction) -> bool {
+        true
     }
+}
 
-    pub fn parse_and_compute(input: &str) -> IResult<&str, i64> {
-        map(
-            fold_many1(
-                parse_instruction,
-                || (Conditional::Do, 0i64),
-                |(state, sum), next| match next {
-                    Instruction::Noop => (state, sum),
-                    Instruction::Conditional(v) => (v, sum),
-                    Instruction::Mul((a, b)) => match state {
-                        Conditional::Do => (state, sum + a * b),
-                        Conditional::Dont => (state, sum)
-                    }
-                },
-            ),
-            |(_, sum)| sum,
-        )(input)
+/// `do()` enables and `don't()` disables the instructions after it,
+/// starting out enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DoDont {
+    enabled: bool,
+}
+
+impl Default for DoDont {
+    fn default() -> Self {
+        DoDont { enabled: true }
     }
+}
 
-    #[allow(dead_code)]
-    fn parse_instructions(input: &str) -> IResult<&str, Vec<Instruction>> {
-        many1(parse_instruction)(input)
+impl Gate for DoDont {
+    fn allows(&mut self, instruction: &Instruction) -> bool {
+        match instruction.name {
+            "do" => self.enabled = true,
+            "don't" => self.enabled = false,
+            _ => return self.enabled,
+        }
+        false
     }
+}
+
+/// What an instruction adds to the total, given its arguments.
+pub type Evaluator = fn(&[i64]) -> i64;
 
-    pub fn parse_muls(input: &str) -> IResult<&str, Vec<(i64, i64)>> {
-        chomp_and_parse(input, parse_mul)
+/// Adds up what every instruction the gate lets through evaluates to.
+/// Instructions without an evaluator are ignored.
+pub struct Interpreter<G> {
+    gate: G,
+    evaluators: Vec<(&'static str, Evaluator)>,
+    total: i64,
+}
+
+impl<G: Gate> Interpreter<G> {
+    pub fn new(gate: G) -> Self {
+        Interpreter { gate, evaluators: Vec::new(), total: 0 }
     }
 
-    fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
-        alt((
-            map(parse_conditional, |c| Instruction::Conditional(c)),
-            map(parse_mul, |pair| Instruction::Mul(pair)),
-            value(Instruction::Noop, take(1usize)),
-        ))(input)
+    /// `gate` with `mul` as the only instruction that does anything.
+    pub fn multiplier(gate: G) -> Self {
+        Self::new(gate).with("mul", |args| args.iter().product())
     }
 
-    fn parse_conditional(input: &str) -> IResult<&str, Conditional> {
-        alt((
-            value(Conditional::Do, tag("do()")),
-            value(Conditional::Dont, tag("don't()")),
-        ))(input)
+    pub fn with(mut self, name: &'static str, evaluate: Evaluator) -> Self {
+        self.evaluators.push((name, evaluate));
+        self
     }
 
-    fn parse_mul(input: &str) -> IResult<&str, (i64, i64)> {
-        delimited(
-            tag("mul("),
-            separated_pair(
-                map_res(digit1, |d: &str| d.parse::<i64>()),
-                tag(","),
-                map_res(digit1, |d: &str| d.parse::<i64>()),
-            ),
-            tag(")"),
-        )(input)
+    pub fn execute(&mut self, instruction: &Instruction) {
+        if !self.gate.allows(instruction) {
+            return;
+        }
+
+        if let Some((_, evaluate)) = self.evaluators.iter().find(|(name, _)| *name == instruction.name) {
+            self.total += evaluate(&instruction.args);
+        }
     }
 
-    #[cfg(test)]
-    mod test {
-        use crate::day3::parser::{
-            parse_instruction, parse_instructions, Conditional, Instruction,
-        };
+    pub fn run(mut self, instructions: impl IntoIterator<Item = Spanned<Instruction>>) -> i64 {
+        for instruction in instructions {
+            self.execute(&instruction.item);
+        }
+        self.total
+    }
+
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
 
-        use super::parse_and_compute;
+    use super::{scan, scan_bytewise, DoDont, Instruction, InstructionTable, Interpreter, Spanned, Ungated};
 
-        #[test]
-        fn test_parse_and_compute() {
-            let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
-            let (_, actual) = parse_and_compute(input).unwrap();
-            assert_eq!(actual, 48);
+    const SAMPLE_1: &str = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+    const SAMPLE_2: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    fn instruction(name: &'static str, args: impl Into<super::Args>) -> Instruction {
+        Instruction { name, args: args.into() }
+    }
+
+    #[test]
+    fn test_scan_spans() {
+        let table = InstructionTable::corrupted_memory();
+        let actual = scan(SAMPLE_2, &table).collect::<Vec<_>>();
+
+        assert_eq!(actual, vec![
+            Spanned { span: 1..9, item: instruction("mul", [2, 4]) },
+            Spanned { span: 20..27, item: instruction("don't", []) },
+            Spanned { span: 28..36, item: instruction("mul", [5, 5]) },
+            Spanned { span: 48..57, item: instruction("mul", [11, 8]) },
+            Spanned { span: 59..63, item: instruction("do", []) },
+            Spanned { span: 64..72, item: instruction("mul", [8, 5]) },
+        ]);
+
+        for Spanned { span, item } in actual {
+            assert!(SAMPLE_2[span].starts_with(item.name));
         }
+    }
+
+    #[test_case(SAMPLE_1)]
+    #[test_case(SAMPLE_2)]
+    #[test_case("mmul(1,2)ddo()mul(1,2,3)mul(99999999999999999999,1)mul(,1)mul(4,5")]
+    #[test_case("ünïcödé mul(1,2) dön't() do()")]
+    fn test_fast_path_matches_bytewise(input: &str) {
+        let table = InstructionTable::corrupted_memory();
+        assert_eq!(scan(input, &table).collect::<Vec<_>>(), scan_bytewise(input, &table).collect::<Vec<_>>());
+
+        // more first bytes than memchr can look for at once
+        let table = table.with("add", 2).with("sub", 2);
+        assert_eq!(scan(input, &table).collect::<Vec<_>>(), scan_bytewise(input, &table).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        let table = InstructionTable::corrupted_memory();
+        let names = scan("mul(1,2,3) mul ( 1,2) mul(1, 2) mul(-1,2) do() don't", &table)
+            .map(|instruction| instruction.item.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["do"]);
+    }
 
-        #[test]
-        fn test_parse_expr_with_cond() {
-            let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
-            let (_, actual) = parse_instructions(input).unwrap();
-            assert_eq!(
-                actual.into_iter().filter(|i| !matches!(i, Instruction::Noop) ).collect::<Vec<_>>(),
-                vec![
-                    Instruction::Mul((2, 4)),
-                    Instruction::Conditional(Conditional::Dont),
-                    Instruction::Mul((5, 5)),
-                    Instruction::Mul((11, 8)),
-                    Instruction::Conditional(Conditional::Do),
-                    Instruction::Mul((8, 5))
-                ]
-            );
+    #[test_case(SAMPLE_1, 161)]
+    #[test_case(SAMPLE_2, 161)]
+    fn test_ungated(input: &str, expected: i64) {
+        let table = InstructionTable::corrupted_memory();
+        assert_eq!(Interpreter::multiplier(Ungated).run(scan(input, &table)), expected);
+    }
+
+    #[test]
+    fn test_do_dont() {
+        let table = InstructionTable::corrupted_memory();
+        assert_eq!(Interpreter::multiplier(DoDont::default()).run(scan(SAMPLE_2, &table)), 48);
+    }
+
+    #[test]
+    fn test_additional_instructions() {
+        let table = InstructionTable::corrupted_memory().with("add", 2).with("neg", 1);
+        let interpreter = Interpreter::multiplier(DoDont::default())
+            .with("add", |args| args.iter().sum())
+            .with("neg", |args| -args[0]);
+
+        let input = "add(1,2)don't()add(100,1)mul(10,10)do()neg(4)mul(2,3)";
+        assert_eq!(interpreter.run(scan(input, &table)), 3 - 4 + 6);
+    }
+}
+
+pub mod parser {
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{char, digit1},
+        combinator::map_res,
+        error::{Error, ErrorKind},
+        IResult,
+    };
+
+    use super::Args;
+
+    fn number(input: &str) -> IResult<&str, i64> {
+        map_res(digit1, |d: &str| d.parse::<i64>())(input)
+    }
+
+    /// `name(a,b,...)` with exactly `arity` non-negative arguments.
+    pub fn call(name: &'static str, arity: usize) -> impl FnMut(&str) -> IResult<&str, Args> {
+        move |input| {
+            let (mut input, _) = tag(name)(input)?;
+            (input, _) = char('(')(input)?;
+
+            let mut args = Args::default();
+            for idx in 0..arity {
+                if idx > 0 {
+                    (input, _) = char(',')(input)?;
+                }
+
+                let (rest, value) = number(input)?;
+                args.push(value).ok_or(nom::Err::Error(Error::new(input, ErrorKind::TooLarge)))?;
+                input = rest;
+            }
+
+            let (input, _) = char(')')(input)?;
+            Ok((input, args))
         }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use test_case::test_case;
+
+        use super::call;
 
-        #[test]
-        fn test_parse_mul() {
-            let input = "mul(2,4)";
-            let (_, actual) = parse_instruction(input).unwrap();
-            assert_eq!(actual, Instruction::Mul((2, 4)));
+        #[test_case("mul(2,4)", "mul", 2, Some(vec![2, 4]))]
+        #[test_case("mul(2,4)rest", "mul", 2, Some(vec![2, 4]))]
+        #[test_case("mul(2)", "mul", 2, None)]
+        #[test_case("mul(2,4,6)", "mul", 2, None)]
+        #[test_case("do()", "do", 0, Some(vec![]))]
+        #[test_case("do(1)", "do", 0, None)]
+        #[test_case("don't()", "do", 0, None)]
+        #[test_case("f(1,2,3,4)", "f", 4, Some(vec![1, 2, 3, 4]))]
+        fn test_call(input: &str, name: &'static str, arity: usize, expected: Option<Vec<i64>>) {
+            let actual = call(name, arity)(input).ok().map(|(_, args)| args.to_vec());
+            assert_eq!(actual, expected);
         }
     }
 }
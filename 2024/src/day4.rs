@@ -1,198 +1,208 @@
-const XMAS: &[u8] = "XMAS".as_bytes();
-const SAMX: &[u8] = "SAMX".as_bytes();
-const MAS: &[u8] = "MAS".as_bytes();
-const SAM: &[u8] = "SAM".as_bytes();
-
-const XMAS_LEN: usize = XMAS.len();
-const MAS_LEN: usize = MAS.len();
-
-pub fn count_x_mas(matrix: &[&[u8]]) -> u32 {
-    let mut count = 0;
+const XMAS: &[u8] = b"XMAS";
+
+/// The X-shaped `MAS` from part 2. Its rotations cover every way the two
+/// `MAS`es can be read.
+pub const X_MAS: [&[u8]; 3] = [b"M.S", b".A.", b"M.S"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
 
-    for y in 0..matrix.len() {
-        if y > matrix.len() - MAS_LEN {
-            break;
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// `(dx, dy)` of a single step, with `y` growing downwards.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
         }
+    }
+}
 
-        for x in 0..matrix[y].len() {
-            if x > matrix[y].len() - MAS_LEN {
-                break;
-            }
+/// A word found in the grid: which of the searched words, where its first
+/// letter sits and which way it reads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WordMatch {
+    pub word_idx: usize,
+    pub start: (usize, usize),
+    pub direction: Direction,
+}
 
-            let candidate = matrix[y..y+3].iter().map(|row| &row[x..x+3]).collect::<Vec<_>>();
+/// Whether `word` reads from `(x, y)` in `direction` without leaving the grid.
+pub fn matches_at(
+    matrix: &[&[u8]],
+    word: &[u8],
+    (x, y): (usize, usize),
+    direction: Direction,
+) -> bool {
+    let (dx, dy) = direction.offset();
+
+    word.iter().enumerate().all(|(step, &letter)| {
+        let step = step as isize;
+        let cell_x = x.checked_add_signed(dx * step);
+        let cell_y = y.checked_add_signed(dy * step);
+
+        cell_y
+            .zip(cell_x)
+            .and_then(|(cell_y, cell_x)| matrix.get(cell_y)?.get(cell_x))
+            == Some(&letter)
+    })
+}
 
-            if check_3_by_3(&candidate) {
-                count += 1;
+/// Every occurrence of every word, in all eight directions.
+///
+/// Palindromes are reported once per direction they read in, just like any
+/// other word.
+pub fn find_words(matrix: &[&[u8]], words: &[&[u8]]) -> Vec<WordMatch> {
+    let mut matches = Vec::new();
+
+    for (y, row) in matrix.iter().enumerate() {
+        for (x, &cell) in row.iter().enumerate() {
+            for (word_idx, word) in words.iter().enumerate() {
+                if word.first() != Some(&cell) {
+                    continue;
+                }
+
+                matches.extend(
+                    Direction::ALL
+                        .into_iter()
+                        .filter(|&direction| matches_at(matrix, word, (x, y), direction))
+                        .map(|direction| WordMatch {
+                            word_idx,
+                            start: (x, y),
+                            direction,
+                        }),
+                );
             }
         }
     }
 
-    count
+    matches
 }
 
-fn check_3_by_3(matrix: &[&[u8]]) -> bool {
-    let dr_candidate = matrix[0..3].iter().enumerate().map(|(i, &c)| c[i]).collect::<Vec<_>>();
-    // dbg!(String::from_utf8(dr_candidate.clone()));
-    let dl_candidate = matrix[0..3].iter().enumerate().map(|(i, &c)| c[2 - i]).collect::<Vec<_>>();
-
-    (dr_candidate == MAS || dr_candidate == SAM) && (dl_candidate == MAS || dl_candidate == SAM)
+/// A small 2D pattern where every cell must match exactly, apart from
+/// [`Stencil::WILDCARD`] cells which match anything.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Stencil {
+    rows: Vec<Vec<u8>>,
 }
 
-pub fn count_xmas(matrix: &[&[u8]]) -> u32 {
-    let mut count = 0;
-
-    for y in 0..matrix.len() {
-        for x in 0..matrix[y].len() {
-            if check_right(matrix, (x, y)) {
-                count += 1;
-            }
-
-            if check_down(matrix, (x, y)) {
-                count += 1;
-            }
+impl Stencil {
+    pub const WILDCARD: u8 = b'.';
 
-            if check_diag_down_right(matrix, (x, y)) {
-                count += 1;
-            }
+    #[must_use]
+    pub fn new(rows: &[&[u8]]) -> Self {
+        assert!(
+            rows.iter().all(|row| row.len() == rows[0].len()),
+            "stencil rows must all be the same width"
+        );
 
-            if check_left(matrix, (x, y)) {
-                count += 1;
-            }
-
-            if check_up(matrix, (x, y)) {
-                count += 1;
-            }
-
-            if check_diag_up_left(matrix, (x, y)) {
-                count += 1;
-            }
-
-            if check_diag_up_right(matrix, (x, y)) {
-                count += 1;
-            }
-
-            if check_diag_down_left(matrix, (x, y)) {
-                count += 1;
-            }
+        Stencil {
+            rows: rows.iter().map(|row| row.to_vec()).collect(),
         }
-    };
-
-    count
-}
-
-fn check_right(matrix: &[&[u8]], (x, y): (usize, usize)) -> bool {
-    // matrix is 10
-    // xmas len is 4
-    // x = 6 is ok
-    // x = 7 is oob
-    if x > matrix[y].len() - XMAS_LEN {
-        return false;
     }
 
-    &matrix[y][x..x+XMAS_LEN] == XMAS
-}
-
-fn check_left(matrix: &[&[u8]], (x, y): (usize, usize)) -> bool {
-    // xmas len - 1 is 3
-    // x is 3, 3 - 3 ok
-    // x is 2, 2 - 3 is underflow
-    if x < XMAS_LEN - 1 {
-        return false;
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, Vec::len)
     }
 
-    &matrix[y][x-3..x+1] == SAMX
-}
-
-fn check_up(matrix: &[&[u8]], (x, y): (usize, usize)) -> bool {
-    // xmas len - 1 is 3
-    // y is 3, 3 - 3 ok
-    // y is 2, 2 - 3 is underflow
-    if y < XMAS_LEN - 1 {
-        return false;
+    pub fn height(&self) -> usize {
+        self.rows.len()
     }
 
-    let candidate = &matrix[y-3..y+1];
-    let x = candidate.iter().map(|&c| c[x]).collect::<Vec<_>>();
-
-    &x == SAMX
-}
+    /// The stencil turned 90 degrees clockwise.
+    #[must_use]
+    pub fn rotated(&self) -> Self {
+        let rows = (0..self.width())
+            .map(|x| self.rows.iter().rev().map(|row| row[x]).collect())
+            .collect();
 
-fn check_down(matrix: &[&[u8]], (x, y): (usize, usize)) -> bool {
-    // matrix len is 10
-    // xmas len is 4
-    // y = 6 ok
-    // y = 7 oob
-    if y > matrix.len() - XMAS_LEN {
-        return false;
+        Stencil { rows }
     }
 
-    let candidate = matrix[y..y+XMAS_LEN].iter().map(|&c| c[x]).collect::<Vec<_>>();
+    /// The distinct stencils produced by rotating this one through a full turn.
+    pub fn rotations(&self) -> Vec<Stencil> {
+        let mut rotations: Vec<Stencil> = Vec::with_capacity(4);
+        let mut next = self.clone();
 
-    &candidate == XMAS
-}
+        for _ in 0..4 {
+            let rotated = next.rotated();
+            if !rotations.contains(&next) {
+                rotations.push(next);
+            }
+            next = rotated;
+        }
 
-fn check_diag_down_right(matrix: &[&[u8]], (x, y): (usize, usize)) -> bool {
-    // CHECK DOWN
-    if y > matrix.len() - XMAS_LEN {
-        return false;
+        rotations
     }
 
-    // CHECK RIGHT
-    // matrix row is 10
-    // xmas is 4
-    // x is 6
-    // 6 + 3 = 9, 9 < 10 ok
-    if x > matrix[y + 3].len() - XMAS_LEN {
-        return false;
+    /// Whether the stencil fits with its top-left corner on `(x, y)`.
+    pub fn matches_at(&self, matrix: &[&[u8]], (x, y): (usize, usize)) -> bool {
+        self.rows.iter().enumerate().all(|(dy, row)| {
+            let Some(cells) = matrix
+                .get(y + dy)
+                .and_then(|line| line.get(x..x + row.len()))
+            else {
+                return false;
+            };
+
+            row.iter()
+                .zip(cells)
+                .all(|(&want, &got)| want == Self::WILDCARD || want == got)
+        })
     }
-
-    let candidate = matrix[y..y+XMAS_LEN].iter().enumerate().map(|(i, &c)| c[x + i]).collect::<Vec<_>>();
-    &candidate == XMAS
 }
 
-fn check_diag_down_left(matrix: &[&[u8]], (x, y): (usize, usize)) -> bool {
-    // CHECK DOWN
-    if y > matrix.len() - XMAS_LEN {
-        return false;
-    }
+/// Top-left corners where any of `stencils` match, along with the index of
+/// the stencil that did.
+pub fn find_stencils(matrix: &[&[u8]], stencils: &[Stencil]) -> Vec<((usize, usize), usize)> {
+    let mut matches = Vec::new();
 
-    // CHECK LEFT
-    if x < XMAS_LEN - 1{
-        return false;
+    for y in 0..matrix.len() {
+        for x in 0..matrix[y].len() {
+            matches.extend(
+                stencils
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, stencil)| stencil.matches_at(matrix, (x, y)))
+                    .map(|(stencil_idx, _)| ((x, y), stencil_idx)),
+            );
+        }
     }
 
-    let candidate = matrix[y..y+4].iter().enumerate().map(|(i, &c)| c[x - i]).collect::<Vec<_>>();
-    &candidate == XMAS
+    matches
 }
 
-fn check_diag_up_right(matrix: &[&[u8]], (x, y): (usize, usize)) -> bool {
-    // CHECK UP
-    if y < XMAS_LEN - 1 {
-        return false;
-    }
-
-    // CHECK RIGHT
-    if x > matrix[y - 3].len() - XMAS_LEN {
-        return false;
-    }
-
-    let candidate = matrix[y-3..y+1].iter().enumerate().map(|(i, &c)| c[x + XMAS_LEN - 1 - i]).collect::<Vec<_>>();
-    &candidate == SAMX
+pub fn count_x_mas(matrix: &[&[u8]]) -> u32 {
+    find_stencils(matrix, &Stencil::new(&X_MAS).rotations()).len() as u32
 }
 
-fn check_diag_up_left(matrix: &[&[u8]], (x, y): (usize, usize)) -> bool {
-    // CHECK UP
-    if y < XMAS_LEN - 1 {
-        return false;
-    }
-
-    // CHECK LEFT
-    if x < XMAS_LEN - 1 {
-        return false;
-    }
-
-    let candidate = matrix[y-3..y+1].iter().enumerate().map(|(i, &c)| c[x - (XMAS_LEN - 1) + i]).collect::<Vec<_>>();
-    &candidate == SAMX
+pub fn count_xmas(matrix: &[&[u8]]) -> u32 {
+    find_words(matrix, &[XMAS]).len() as u32
 }
 
 #[cfg(test)]
@@ -200,9 +210,10 @@ mod test {
     use std::sync::LazyLock;
 
     use test_case::test_case;
-    use crate::day4::{check_3_by_3, check_diag_down_left, check_diag_down_right, check_diag_up_left, check_diag_up_right, check_down, check_left, check_right, check_up, count_x_mas};
-
-    use super::count_xmas;
+    use super::{
+        count_x_mas, count_xmas, find_stencils, find_words, matches_at, Direction, Stencil,
+        WordMatch, XMAS, X_MAS,
+    };
 
     pub static SAMPLE: LazyLock<Vec<&[u8]>> = LazyLock::new(|| vec![
             "MMMSXXMASM".as_bytes(),
@@ -251,7 +262,11 @@ mod test {
         "M.S".as_bytes(),
     ], true)]
     fn test_check_3_by_3(matrix: Vec<&[u8]>, expected: bool) {
-        assert_eq!(check_3_by_3(&matrix), expected);
+        let stencils = Stencil::new(&X_MAS).rotations();
+        assert_eq!(
+            stencils.iter().any(|s| s.matches_at(&matrix, (0, 0))),
+            expected
+        );
     }
 
     #[test_case(vec!["XMAS".as_bytes()], (0,0), true)]
@@ -259,7 +274,7 @@ mod test {
     #[test_case(vec!["XMAS".as_bytes()], (2,0), false)]
     #[test_case(vec!["XMAS".as_bytes()], (3,0), false)]
     fn test_check_right(matrix: Vec<&[u8]>, idx: (usize, usize), expected: bool) {
-        let actual = check_right(&matrix, idx);
+        let actual = matches_at(&matrix, XMAS, idx, Direction::East);
         assert_eq!(actual, expected);
     }
     
@@ -268,7 +283,7 @@ mod test {
     #[test_case(vec!["SAMX".as_bytes()], (2,0), false)]
     #[test_case(vec!["SAMX".as_bytes()], (3,0), true)]
     fn test_check_left(matrix: Vec<&[u8]>, idx: (usize, usize), expected: bool) {
-        let actual = check_left(&matrix, idx);
+        let actual = matches_at(&matrix, XMAS, idx, Direction::West);
         assert_eq!(actual, expected);
     }
 
@@ -297,7 +312,7 @@ mod test {
         "XXXX".as_bytes(),
     ], (0,3), true)]
     fn test_check_up(matrix: Vec<&[u8]>, idx: (usize, usize), expected: bool) {
-        let actual = check_up(&matrix, idx);
+        let actual = matches_at(&matrix, XMAS, idx, Direction::North);
         assert_eq!(actual, expected);
     }
 
@@ -326,7 +341,7 @@ mod test {
         "SXXX".as_bytes(),
     ], (0,3), false)]
     fn test_check_down(matrix: Vec<&[u8]>, idx: (usize, usize), expected: bool) {
-        let actual = check_down(&matrix, idx);
+        let actual = matches_at(&matrix, XMAS, idx, Direction::South);
         assert_eq!(actual, expected);
     }
 
@@ -355,7 +370,7 @@ mod test {
         "XXXS".as_bytes(),
     ], (1,1), false)]
     fn test_check_diag_down_right(matrix: Vec<&[u8]>, idx: (usize, usize), expected: bool) {
-        let actual = check_diag_down_right(&matrix, idx);
+        let actual = matches_at(&matrix, XMAS, idx, Direction::SouthEast);
         assert_eq!(actual, expected);
     }
 
@@ -372,7 +387,7 @@ mod test {
         "SXXX".as_bytes(),
     ], (2,2), false)]
     fn test_check_diag_down_left(matrix: Vec<&[u8]>, idx: (usize, usize), expected: bool) {
-        let actual = check_diag_down_left(&matrix, idx);
+        let actual = matches_at(&matrix, XMAS, idx, Direction::SouthWest);
         assert_eq!(actual, expected);
     }
 
@@ -389,7 +404,7 @@ mod test {
         "XXXX".as_bytes(),
     ], (1,2), false)]
     fn test_check_diag_up_right(matrix: Vec<&[u8]>, idx: (usize, usize), expected: bool) {
-        let actual = check_diag_up_right(&matrix, idx);
+        let actual = matches_at(&matrix, XMAS, idx, Direction::NorthEast);
         assert_eq!(actual, expected);
     }
 
@@ -400,9 +415,72 @@ mod test {
         "XXXX".as_bytes(),
     ], (3,3), true)]
     fn test_check_diag_up_left(matrix: Vec<&[u8]>, idx: (usize, usize), expected: bool) {
-        let actual = check_diag_up_left(&matrix, idx);
+        let actual = matches_at(&matrix, XMAS, idx, Direction::NorthWest);
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_find_words_reports_positions_and_directions() {
+        let matrix: Vec<&[u8]> = vec![b"XMAS", b"M...", b"A...", b"S..."];
+        let matches = find_words(&matrix, &[XMAS]);
+
+        assert_eq!(
+            matches,
+            vec![
+                WordMatch { word_idx: 0, start: (0, 0), direction: Direction::East },
+                WordMatch { word_idx: 0, start: (0, 0), direction: Direction::South },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_several_words() {
+        let matrix: Vec<&[u8]> = vec![b"CAT", b"ODO", b"GOD"];
+        let mut matches = find_words(&matrix, &[b"CAT", b"DOG", b"COG"]);
+        matches.sort_by_key(|m| m.word_idx);
+
+        assert_eq!(
+            matches,
+            vec![
+                WordMatch { word_idx: 0, start: (0, 0), direction: Direction::East },
+                WordMatch { word_idx: 1, start: (2, 2), direction: Direction::West },
+                WordMatch { word_idx: 2, start: (0, 0), direction: Direction::South },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_x_mas_has_four_rotations() {
+        let rotations = Stencil::new(&X_MAS).rotations();
+
+        assert_eq!(rotations.len(), 4);
+        assert!(rotations.iter().all(|s| s.width() == 3 && s.height() == 3));
+    }
+
+    #[test]
+    fn test_symmetric_stencil_rotations_dedupe() {
+        let plus = Stencil::new(&[b".A.", b"AAA", b".A."]);
+        assert_eq!(plus.rotations(), vec![plus]);
+    }
+
+    #[test]
+    fn test_rotated() {
+        let stencil = Stencil::new(&[b"AB", b"CD", b"EF"]);
+        assert_eq!(stencil.rotated(), Stencil::new(&[b"ECA", b"FDB"]));
+    }
+
+    #[test]
+    fn test_find_stencils() {
+        let plus = Stencil::new(&[b".M.", b"MAS", b".S."]);
+        let matrix: Vec<&[u8]> = vec![b"XXMX", b"XMAS", b"MXSX", b"XXXX"];
+        let matches = find_stencils(&matrix, &[Stencil::new(&[b"X"]), plus]);
+
+        assert_eq!(
+            matches.iter().filter(|(_, idx)| *idx == 1).collect::<Vec<_>>(),
+            vec![&((1, 0), 1)]
+        );
+        assert_eq!(matches.iter().filter(|(_, idx)| *idx == 0).count(), 10);
+    }
 }
 pub mod parser {
 
@@ -1,69 +1,124 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct PageNumber(u32);
 
-pub type Update<'a> = Vec<Page<'a>>;
-type OrderingRules = HashMap<PageNumber, HashMap<PageNumber, Ordering>>;
+impl PageNumber {
+    pub fn number(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Every page along with the pages a rule says must come after it.
+///
+/// The rules only relate some pairs of pages and aren't transitive, so they
+/// don't order pages on their own. Use [`fix_update`] to order a whole update.
+pub type OrderingRules = HashMap<PageNumber, HashSet<PageNumber>>;
+
+/// Whether a rule puts `earlier` before `later`.
+fn must_precede(rules: &OrderingRules, earlier: PageNumber, later: PageNumber) -> bool {
+    rules.get(&earlier).is_some_and(|after| after.contains(&later))
+}
 
-#[derive(Debug, Clone, Copy, Eq)]
-pub struct Page<'a> {
-    page_number: PageNumber,
-    ordering: &'a OrderingRules 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    /// The rules between these pages go round in a circle: each page must
+    /// come before the next, and the last before the first. Starts from the
+    /// lowest page number.
+    Cycle(Vec<PageNumber>),
 }
 
-impl<'a> Page<'a> {
-    #[must_use]
-    pub fn new(page_number: PageNumber, ordering: &'a OrderingRules) -> Self {
-        Page {
-            page_number,
-            ordering
+/// Whether no rule puts a later page of `update` before an earlier one.
+pub fn is_ordered(rules: &OrderingRules, update: &[PageNumber]) -> bool {
+    update.iter().enumerate().all(|(idx, &earlier)| {
+        update[idx + 1..]
+            .iter()
+            .all(|&later| !must_precede(rules, later, earlier))
+    })
+}
+
+/// The update reordered to satisfy every rule between its pages, using
+/// Kahn's algorithm and only considering rules between pages that are
+/// actually in the update.
+///
+/// When several pages could go next the one that came first in `update` wins,
+/// so an update that's already ordered comes back unchanged.
+pub fn fix_update(
+    rules: &OrderingRules,
+    update: &[PageNumber],
+) -> Result<Vec<PageNumber>, OrderError> {
+    // predecessors[i] holds the indices of pages that must come before update[i]
+    let predecessors = update
+        .iter()
+        .map(|&page| {
+            update
+                .iter()
+                .enumerate()
+                .filter(|&(_, &other)| must_precede(rules, other, page))
+                .map(|(idx, _)| idx)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut in_degree = predecessors.iter().map(Vec::len).collect::<Vec<_>>();
+    let mut placed = vec![false; update.len()];
+    let mut order = Vec::with_capacity(update.len());
+
+    while let Some(next) = (0..update.len()).find(|&idx| !placed[idx] && in_degree[idx] == 0) {
+        placed[next] = true;
+        order.push(update[next]);
+
+        for (idx, preds) in predecessors.iter().enumerate() {
+            if preds.contains(&next) {
+                in_degree[idx] -= 1;
+            }
         }
     }
 
-    pub fn number(&self) -> u32 {
-        self.page_number.0
+    if order.len() < update.len() {
+        return Err(OrderError::Cycle(find_cycle(update, &predecessors, &placed)));
     }
-}
 
-impl<'a> PartialEq for Page<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        self.ordering.get(&self.page_number)
-            .and_then(|inner| inner.get(&other.page_number)).is_none()
-    }
+    Ok(order)
 }
 
-impl<'a> PartialOrd for Page<'a> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.ordering.get(&self.page_number)
-            .and_then(|inner| inner.get(&other.page_number).copied())
+/// Every page Kahn's algorithm couldn't place still has an unplaced
+/// predecessor, so walking predecessors must eventually revisit a page.
+fn find_cycle(
+    update: &[PageNumber],
+    predecessors: &[Vec<usize>],
+    placed: &[bool],
+) -> Vec<PageNumber> {
+    let mut walk = vec![placed.iter().position(|&p| !p).unwrap()];
+
+    loop {
+        let current = *walk.last().unwrap();
+        let pred = *predecessors[current].iter().find(|&&idx| !placed[idx]).unwrap();
+
+        if let Some(start) = walk.iter().position(|&idx| idx == pred) {
+            // the walk went backwards through the rules, flip it to read forwards
+            let mut cycle = walk[start..].iter().rev().map(|&idx| update[idx]).collect::<Vec<_>>();
+            let lowest = (0..cycle.len()).min_by_key(|&idx| cycle[idx].number()).unwrap();
+            cycle.rotate_left(lowest);
+            return cycle;
+        }
+
+        walk.push(pred);
     }
 }
 
-impl<'a> Ord for Page<'a> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.ordering.get(&self.page_number)
-            .and_then(|inner| inner.get(&other.page_number).copied())
-            .unwrap_or(Ordering::Equal)
-    }
+/// The page in the middle of `update`, `None` if it's empty.
+pub fn middle_page(update: &[PageNumber]) -> Option<u32> {
+    update.get(update.len() / 2).map(PageNumber::number)
 }
 
 #[cfg(test)]
 mod test {
-    use std::cmp::Ordering;
-
     use test_case::test_case;
 
-    use super::{parser, Page, PageNumber};
+    use super::{fix_update, is_ordered, middle_page, parser, OrderError, PageNumber};
 
-    #[test_case(vec![75,47,61,53,29], true)]
-    #[test_case(vec![97,61,53,29,13], true)]
-    #[test_case(vec![75,29,13], true)]
-    #[test_case(vec![75,97,47,61,53], false)]
-    #[test_case(vec![61,13,29], false)]
-    #[test_case(vec![97,13,75,29,47], false)]
-    fn test_is_sorted(raw_pages: Vec<u32>, expected: bool) {
-        let sample_rules = r#"47|53
+    const SAMPLE_RULES: &str = r#"47|53
 97|13
 97|61
 97|47
@@ -86,64 +141,104 @@ mod test {
 53|13
 "#;
 
-        let (_, rules) = parser::ordering_rules(sample_rules).unwrap();
-        let pages = raw_pages.into_iter()
-            .map(|pg| Page::new(PageNumber(pg), &rules));
+    fn pages(raw_pages: &[u32]) -> Vec<PageNumber> {
+        raw_pages.iter().copied().map(PageNumber).collect()
+    }
+
+    #[test_case(vec![75,47,61,53,29], true)]
+    #[test_case(vec![97,61,53,29,13], true)]
+    #[test_case(vec![75,29,13], true)]
+    #[test_case(vec![75,97,47,61,53], false)]
+    #[test_case(vec![61,13,29], false)]
+    #[test_case(vec![97,13,75,29,47], false)]
+    fn test_is_ordered(raw_pages: Vec<u32>, expected: bool) {
+        let (_, rules) = parser::ordering_rules(SAMPLE_RULES).unwrap();
+        let update = pages(&raw_pages);
+
+        assert_eq!(is_ordered(&rules, &update), expected);
+        assert_eq!(fix_update(&rules, &update).unwrap() == update, expected);
+    }
+
+    #[test_case(vec![75,97,47,61,53], vec![97,75,47,61,53])]
+    #[test_case(vec![61,13,29], vec![61,29,13])]
+    #[test_case(vec![97,13,75,29,47], vec![97,75,47,29,13])]
+    #[test_case(vec![75,47,61,53,29], vec![75,47,61,53,29])]
+    fn test_fix_update(raw_pages: Vec<u32>, expected: Vec<u32>) {
+        let (_, rules) = parser::ordering_rules(SAMPLE_RULES).unwrap();
+
+        assert_eq!(fix_update(&rules, &pages(&raw_pages)), Ok(pages(&expected)));
+    }
+
+    #[test]
+    fn test_unrelated_pages_keep_their_order() {
+        let (_, rules) = parser::ordering_rules("3|4\n").unwrap();
+
+        assert_eq!(fix_update(&rules, &pages(&[4, 9, 1, 3])), Ok(pages(&[9, 1, 3, 4])));
+    }
+
+    #[test]
+    fn test_rules_outside_the_update_are_ignored() {
+        // 1 < 2 < 3 < 1 is a cycle, but without 3 in the update it never matters
+        let (_, rules) = parser::ordering_rules("1|2\n2|3\n3|1\n").unwrap();
 
-        assert_eq!(pages.is_sorted(), expected);
+        assert_eq!(fix_update(&rules, &pages(&[2, 1])), Ok(pages(&[1, 2])));
     }
 
-    #[test_case(1, 2, "3|4\n", true)]
-    #[test_case(3, 4, "3|4\n", false)]
-    fn test_page_partial_eq(a: u32, b: u32, rule: &str, expected: bool) {
-        let (_, rules) = parser::ordering_rules(rule).unwrap();
-        let page_a = Page::new(PageNumber(a), &rules);
-        let page_b = Page::new(PageNumber(b), &rules);
+    #[test]
+    fn test_cycle_is_reported() {
+        let (_, rules) = parser::ordering_rules("1|2\n2|3\n3|1\n4|1\n").unwrap();
 
-        assert_eq!(page_a == page_b, expected);
+        assert_eq!(
+            fix_update(&rules, &pages(&[4, 3, 2, 1])),
+            Err(OrderError::Cycle(pages(&[1, 2, 3])))
+        );
     }
 
-    #[test_case(1, 2, "3|4\n", Ordering::Equal)]
-    #[test_case(3, 4, "3|4\n", Ordering::Less)]
-    #[test_case(4, 3, "3|4\n", Ordering::Greater)]
-    fn test_page_ord(a: u32, b: u32, rule: &str, expected: Ordering) {
-        let (_, rules) = parser::ordering_rules(rule).unwrap();
-        let page_a = Page::new(PageNumber(a), &rules);
-        let page_b = Page::new(PageNumber(b), &rules);
+    #[test]
+    fn test_contradicting_pair_is_a_cycle() {
+        let (_, rules) = parser::ordering_rules("1|2\n2|1\n").unwrap();
 
-        assert_eq!(page_a.cmp(&page_b), expected);
+        assert!(!is_ordered(&rules, &pages(&[1, 2])));
+        assert!(!is_ordered(&rules, &pages(&[2, 1])));
+        assert_eq!(fix_update(&rules, &pages(&[1, 2])), Err(OrderError::Cycle(pages(&[1, 2]))));
+        assert_eq!(fix_update(&rules, &pages(&[2, 1])), Err(OrderError::Cycle(pages(&[1, 2]))));
+    }
+
+    #[test_case(vec![75,47,61,53,29], Some(61))]
+    #[test_case(vec![75,29,13], Some(29))]
+    #[test_case(vec![], None)]
+    fn test_middle_page(raw_pages: Vec<u32>, expected: Option<u32>) {
+        assert_eq!(middle_page(&pages(&raw_pages)), expected);
     }
  }
 
 pub mod parser {
-    use std::{cmp::Ordering, collections::HashMap};
-    use nom::{bytes::complete::tag, character::complete::{newline, u32}, combinator::map, multi::{fold_many1, separated_list1}, sequence::{separated_pair, terminated}, IResult};
+    use std::collections::HashMap;
+    use aoc_parse::{blank_line, lines, separated, unsigned};
+    use nom::{bytes::complete::tag, combinator::map, sequence::separated_pair, IResult};
 
     use super::{PageNumber, OrderingRules};
 
     type UpdatedPageNumbers = Vec<Vec<PageNumber>>;
 
     pub fn input_sections(input: &str) -> IResult<&str, (OrderingRules, UpdatedPageNumbers)> {
-        separated_pair(ordering_rules, newline, updates)(input)
+        separated_pair(ordering_rules, blank_line, updates)(input)
     }
 
     fn updates(input: &str) -> IResult<&str, UpdatedPageNumbers> {
-        separated_list1(newline, update)(input)
+        lines(update)(input)
     }
 
     fn update(input: &str) -> IResult<&str, Vec<PageNumber>> {
-        separated_list1(tag(","), page_number)(input)
+        separated(',', page_number)(input)
     }
 
     pub fn ordering_rules(input: &str) -> IResult<&str, OrderingRules> {
-        fold_many1(terminated(ordering, newline), || HashMap::new(), |mut map, (a, b)| {
-             let inner_map = map.entry(a).or_insert(HashMap::new());
-             inner_map.insert(b, Ordering::Less);
-
-             let inner_map = map.entry(b).or_insert(HashMap::new());
-             inner_map.insert(a, Ordering::Greater);
-
-            map
+        map(lines(ordering), |orderings| {
+            orderings.into_iter().fold(HashMap::new(), |mut map: OrderingRules, (a, b)| {
+                map.entry(a).or_default().insert(b);
+                map
+            })
         })(input)
     }
 
@@ -155,17 +250,21 @@ pub mod parser {
     }
 
     fn page_number(input: &str) -> IResult<&str, PageNumber> {
-        map(u32, |v| PageNumber(v))(input)
+        map(unsigned, PageNumber)(input)
     }
 
     #[cfg(test)]
     mod test {
-        use std::{cmp::Ordering, collections::HashMap};
+        use std::collections::{HashMap, HashSet};
         use test_case::test_case;
         use crate::day5::{parser::{ordering,update,UpdatedPageNumbers}, OrderingRules, PageNumber,};
 
         use super::{input_sections, ordering_rules, updates};
 
+        fn successors(pages: &[u32]) -> HashSet<PageNumber> {
+            pages.iter().copied().map(PageNumber).collect()
+        }
+
         #[test]
         fn test_parse_sample_input() {
             let input = r#"47|53
@@ -209,62 +308,12 @@ pub mod parser {
             .collect();
 
             let expected_ordering: OrderingRules = HashMap::from([
-                (PageNumber(13), HashMap::from([
-                    (PageNumber(29), Ordering::Greater),
-                    (PageNumber(47), Ordering::Greater),
-                    (PageNumber(53), Ordering::Greater),
-                    (PageNumber(61), Ordering::Greater),
-                    (PageNumber(75), Ordering::Greater),
-                    (PageNumber(97), Ordering::Greater),
-                ])),
-                (PageNumber(29), HashMap::from([
-                    (PageNumber(13), Ordering::Less),
-                    (PageNumber(47), Ordering::Greater),
-                    (PageNumber(53), Ordering::Greater),
-                    (PageNumber(61), Ordering::Greater),
-                    (PageNumber(75), Ordering::Greater),
-                    (PageNumber(97), Ordering::Greater),
-                ])),
-                (PageNumber(47), HashMap::from([
-                    (PageNumber(13), Ordering::Less),
-                    (PageNumber(29), Ordering::Less),
-                    (PageNumber(53), Ordering::Less),
-                    (PageNumber(61), Ordering::Less),
-                    (PageNumber(75), Ordering::Greater),
-                    (PageNumber(97), Ordering::Greater),
-                ])),
-                (PageNumber(53), HashMap::from([
-                    (PageNumber(13), Ordering::Less),
-                    (PageNumber(29), Ordering::Less),
-                    (PageNumber(47), Ordering::Greater),
-                    (PageNumber(61), Ordering::Greater),
-                    (PageNumber(75), Ordering::Greater),
-                    (PageNumber(97), Ordering::Greater),
-                ])),
-                (PageNumber(61), HashMap::from([
-                    (PageNumber(13), Ordering::Less),
-                    (PageNumber(29), Ordering::Less),
-                    (PageNumber(47), Ordering::Greater),
-                    (PageNumber(53), Ordering::Less),
-                    (PageNumber(75), Ordering::Greater),
-                    (PageNumber(97), Ordering::Greater),
-                ])),
-                (PageNumber(75), HashMap::from([
-                    (PageNumber(13), Ordering::Less),
-                    (PageNumber(29), Ordering::Less),
-                    (PageNumber(47), Ordering::Less),
-                    (PageNumber(53), Ordering::Less),
-                    (PageNumber(61), Ordering::Less),
-                    (PageNumber(97), Ordering::Greater),
-                ])),
-                (PageNumber(97), HashMap::from([
-                    (PageNumber(13), Ordering::Less),
-                    (PageNumber(29), Ordering::Less),
-                    (PageNumber(47), Ordering::Less),
-                    (PageNumber(53), Ordering::Less),
-                    (PageNumber(61), Ordering::Less),
-                    (PageNumber(75), Ordering::Less),
-                ])),
+                (PageNumber(29), successors(&[13])),
+                (PageNumber(47), successors(&[13, 29, 53, 61])),
+                (PageNumber(53), successors(&[13, 29])),
+                (PageNumber(61), successors(&[13, 29, 53])),
+                (PageNumber(75), successors(&[13, 29, 47, 53, 61])),
+                (PageNumber(97), successors(&[13, 29, 47, 53, 61, 75])),
             ]);
 
             let (_, (actual_orderings, actual_updates)) = input_sections(input).unwrap();
@@ -282,39 +331,8 @@ pub mod parser {
 "#;
 
             let expected: OrderingRules = HashMap::from([
-                (
-                    PageNumber(13),
-                    HashMap::from([
-                        (PageNumber(97), Ordering::Greater),
-                    ]),
-                ),
-                (
-                    PageNumber(47),
-                    HashMap::from([
-                        (PageNumber(53), Ordering::Less),
-                        (PageNumber(97), Ordering::Greater),
-                    ]),
-                ),
-                (
-                    PageNumber(53),
-                    HashMap::from([
-                        (PageNumber(47), Ordering::Greater),
-                    ]),
-                ),
-                (
-                    PageNumber(61),
-                    HashMap::from([
-                        (PageNumber(97), Ordering::Greater),
-                    ]),
-                ),
-                (
-                    PageNumber(97),
-                    HashMap::from([
-                        (PageNumber(13), Ordering::Less),
-                        (PageNumber(47), Ordering::Less),
-                        (PageNumber(61), Ordering::Less),
-                    ]),
-                ),
+                (PageNumber(47), successors(&[53])),
+                (PageNumber(97), successors(&[13, 47, 61])),
             ]);
 
             let (_, actual) = ordering_rules(input).unwrap();
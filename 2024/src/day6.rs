@@ -1,4 +1,8 @@
+use std::collections::HashSet;
+
+use aoc_geometry::{Heading, Vec2};
 use itertools::Itertools;
+use rayon::prelude::*;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RowIdx(pub usize);
@@ -74,47 +78,102 @@ pub struct World {
     grid: Vec<Vec<GridItem>>,
     grid_size: (usize, usize),
     guard_loc: Position,
-    guard_direction: Direction,
-}
-
-impl Direction {
-    pub fn next(&self) -> Self {
-        match self {
-            Direction::North => Direction::East,
-            Direction::South => Direction::West,
-            Direction::East => Direction::South,
-            Direction::West => Direction::North,
-        }
-    }
+    guard_direction: Heading,
 }
 
 impl World {
+    /// Panics unless the guard is at `guard_loc`.
     #[must_use]
-    fn new(grid: Vec<Vec<GridItem>>) -> Self {
+    fn new(grid: Vec<Vec<GridItem>>, guard_loc: Position) -> Self {
         let grid_size = (grid.len(), grid[0].len());
-        // This is probably dumb, but i dont' feel like figuring out how to
-        // track indexes in the nom parser so that this can just be known during parse.
-        // I'm sure it's possible but it's almost 7pm and i'm tired!
-
-        let guard_loc = grid
-            .iter()
-            .flatten()
-            .enumerate()
-            .find(|(_, item)| matches!(item, GridItem::Guard { .. }))
-            .map(|(idx, _)| Position::from((RowIdx(idx / grid_size.1), ColIdx(idx % grid_size.1))))
-            .unwrap();
+        let GridItem::Guard(guard) = grid[guard_loc.row_idx.0][guard_loc.col_idx.0] else {
+            panic!("no guard at {guard_loc:?}");
+        };
 
         World {
             grid,
             guard_loc,
             grid_size,
-            guard_direction: Direction::North,
+            guard_direction: guard.facing,
+        }
+    }
+
+    fn item(&self, position: Position) -> GridItem {
+        self.grid[position.row_idx.0][position.col_idx.0]
+    }
+
+    /// The neighbouring position in `direction`, if it's still on the map.
+    fn step(&self, position: Position, direction: Heading) -> Option<Position> {
+        let here = Vec2::<isize>::from_grid((position.row_idx.0, position.col_idx.0))?;
+        let next = here.step(direction);
+        let (row, col) = next.to_grid(self.grid_size.1, self.grid_size.0)?;
+
+        Some(Position::from((RowIdx(row), ColIdx(col))))
+    }
+
+    /// Every cell the guard stands on, in order, with the heading it had
+    /// there. Turning in place shows up as the same cell twice.
+    ///
+    /// Stops when the guard walks off the map, or just before it would repeat
+    /// a state if it's stuck in a loop.
+    pub fn trace(&self) -> Vec<(Position, Heading)> {
+        let mut seen = HashSet::new();
+        let mut trace = Vec::new();
+        let (mut position, mut direction) = (self.guard_loc, self.guard_direction);
+
+        while seen.insert((position, direction)) {
+            trace.push((position, direction));
+
+            match self.step(position, direction) {
+                None => break,
+                Some(next) if self.item(next) == GridItem::Rock => {
+                    direction = direction.turn_right();
+                }
+                Some(next) => position = next,
+            }
         }
+
+        trace
+    }
+
+    /// The map with the guard's route drawn on as in the puzzle: `|` and `-`
+    /// for vertical and horizontal moves, `+` where it turns or crosses over
+    /// itself.
+    pub fn render_trace(&self) -> String {
+        let (rows, cols) = self.grid_size;
+        let mut marks = vec![vec![None; cols]; rows];
+
+        for (position, direction) in self.trace() {
+            let mark = match direction {
+                Heading::North | Heading::South => '|',
+                Heading::East | Heading::West => '-',
+            };
+            let cell = &mut marks[position.row_idx.0][position.col_idx.0];
+            *cell = match *cell {
+                Some(existing) if existing != mark => Some('+'),
+                _ => Some(mark),
+            };
+        }
+
+        let mut rendered = String::with_capacity(rows * (cols + 1));
+        for (row_idx, row) in self.grid.iter().enumerate() {
+            for (col_idx, item) in row.iter().enumerate() {
+                let position = Position::from((RowIdx(row_idx), ColIdx(col_idx)));
+                rendered.push(match marks[row_idx][col_idx] {
+                    _ if position == self.guard_loc => self.guard_direction.arrow(),
+                    Some(mark) => mark,
+                    None => item.glyph(),
+                });
+            }
+            rendered.push('\n');
+        }
+
+        rendered
     }
 
     pub fn next_rock_in_sight(&self) -> Result<Position,Position> {
         let iter: Box<dyn Iterator<Item = (Position, GridItem)>> = match self.guard_direction {
-            Direction::North => Box::new(
+            Heading::North => Box::new(
                 self.grid[0..self.guard_loc.row_idx.0]
                     .iter()
                     .enumerate()
@@ -126,7 +185,7 @@ impl World {
                     })
                     .rev(),
             ),
-            Direction::South => Box::new(
+            Heading::South => Box::new(
                 self.grid[self.guard_loc.row_idx.0..]
                     .iter()
                     .enumerate()
@@ -141,7 +200,7 @@ impl World {
                         )
                     }),
             ),
-            Direction::West => Box::new(
+            Heading::West => Box::new(
                 self.grid[self.guard_loc.row_idx.0][0..self.guard_loc.col_idx.0]
                     .iter()
                     .enumerate()
@@ -153,7 +212,7 @@ impl World {
                     })
                     .rev(),
             ),
-            Direction::East => Box::new(
+            Heading::East => Box::new(
                 self.grid[self.guard_loc.row_idx.0][self.guard_loc.col_idx.0..]
                     .iter()
                     .enumerate()
@@ -182,14 +241,14 @@ impl World {
     pub fn walk_guard(&mut self) -> Result<Position, Position> {
         let pos = self.next_rock_in_sight().map(|pos| {
             Position::from(match self.guard_direction {
-                Direction::North => (RowIdx(pos.row_idx.0 + 1), pos.col_idx),
-                Direction::South => (RowIdx(pos.row_idx.0 - 1), pos.col_idx),
-                Direction::East => (pos.row_idx, ColIdx(pos.col_idx.0 - 1)),
-                Direction::West => (pos.row_idx, ColIdx(pos.col_idx.0 + 1)),
+                Heading::North => (RowIdx(pos.row_idx.0 + 1), pos.col_idx),
+                Heading::South => (RowIdx(pos.row_idx.0 - 1), pos.col_idx),
+                Heading::East => (pos.row_idx, ColIdx(pos.col_idx.0 - 1)),
+                Heading::West => (pos.row_idx, ColIdx(pos.col_idx.0 + 1)),
             })
         });
 
-        self.guard_direction = self.guard_direction.next();
+        self.guard_direction = self.guard_direction.turn_right();
 
         let real_pos = match pos {
             Ok(pos) => pos,
@@ -215,7 +274,7 @@ impl World {
         &self.guard_loc
     }
 
-    pub fn guard_direction(&self) -> &Direction {
+    pub fn guard_direction(&self) -> &Heading {
         &self.guard_direction
     }
 
@@ -228,27 +287,318 @@ impl World {
 enum GridItem {
     Empty,
     Rock,
-    Guard,
+    Guard(Guard),
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
-pub enum Direction {
-    #[default]
-    North,
-    South,
-    East,
-    West,
+impl GridItem {
+    fn glyph(&self) -> char {
+        match self {
+            GridItem::Empty => '.',
+            GridItem::Rock => '#',
+            GridItem::Guard(guard) => guard.facing.arrow(),
+        }
+    }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Guard {
-    facing: Direction,
+    facing: Heading,
+}
+
+impl Guard {
+    #[must_use]
+    pub fn new(facing: Heading) -> Self {
+        Guard { facing }
+    }
+
+    pub fn facing(&self) -> Heading {
+        self.facing
+    }
+}
+
+// Marks a jump that walks the guard off the map.
+const EXIT: u32 = u32::MAX;
+
+/// For every cell and heading, where the guard ends up when it next bumps into
+/// a rock, so a walk costs one lookup per turn instead of one per step.
+///
+/// Cells are flattened to `row * cols + col`.
+#[derive(Clone, Debug)]
+pub struct JumpTable {
+    rows: usize,
+    cols: usize,
+    rocks: Vec<bool>,
+    stops: Vec<[u32; 4]>,
+}
+
+/// The jump table entries overwritten by [`JumpTable::place_obstacle`].
+#[derive(Debug)]
+pub struct PlacedObstacle {
+    cell: usize,
+    overwritten: Vec<(usize, Heading, u32)>,
+}
+
+impl JumpTable {
+    #[must_use]
+    pub fn new(world: &World) -> Self {
+        let (rows, cols) = world.grid_size;
+        let rocks = world
+            .grid
+            .iter()
+            .flatten()
+            .map(|item| matches!(item, GridItem::Rock))
+            .collect::<Vec<_>>();
+        let mut stops = vec![[EXIT; 4]; rows * cols];
+
+        // sweep each row and column, remembering the cell in front of the last
+        // rock seen, which is where a guard walking back towards it stops
+        let mut sweep = |cells: &mut dyn Iterator<Item = usize>, direction: Heading, back: isize| {
+            let mut stop = EXIT;
+            for cell in cells {
+                if rocks[cell] {
+                    stop = cell.wrapping_add_signed(back) as u32;
+                } else {
+                    stops[cell][direction.index()] = stop;
+                }
+            }
+        };
+
+        let cols_i = cols as isize;
+        for row in 0..rows {
+            sweep(&mut (0..cols).map(|col| row * cols + col), Heading::West, 1);
+            sweep(&mut (0..cols).rev().map(|col| row * cols + col), Heading::East, -1);
+        }
+        for col in 0..cols {
+            sweep(&mut (0..rows).map(|row| row * cols + col), Heading::North, cols_i);
+            sweep(&mut (0..rows).rev().map(|row| row * cols + col), Heading::South, -cols_i);
+        }
+
+        JumpTable {
+            rows,
+            cols,
+            rocks,
+            stops,
+        }
+    }
+
+    pub fn cell(&self, position: &Position) -> usize {
+        position.row_idx.0 * self.cols + position.col_idx.0
+    }
+
+    pub fn position(&self, cell: usize) -> Position {
+        Position::from((RowIdx(cell / self.cols), ColIdx(cell % self.cols)))
+    }
+
+    /// Where the guard stops walking from `cell` towards `direction`, or
+    /// `None` if it walks off the map.
+    pub fn next_stop(&self, cell: usize, direction: Heading) -> Option<usize> {
+        match self.stops[cell][direction.index()] {
+            EXIT => None,
+            stop => Some(stop as usize),
+        }
+    }
+
+    /// Adds a rock, only touching the entries of cells in its row and column
+    /// that now stop in front of it. Hand the result to
+    /// [`JumpTable::remove_obstacle`] to put the table back.
+    pub fn place_obstacle(&mut self, position: Position) -> PlacedObstacle {
+        let (row, col) = (position.row_idx.0, position.col_idx.0);
+        let cell = self.cell(&position);
+        let mut overwritten = Vec::new();
+
+        assert!(!self.rocks[cell], "there's already a rock at {position:?}");
+        self.rocks[cell] = true;
+        for direction in [Heading::North, Heading::South, Heading::East, Heading::West] {
+            let entry = &mut self.stops[cell][direction.index()];
+            overwritten.push((cell, direction, *entry));
+            *entry = EXIT;
+        }
+
+        let cols = self.cols;
+        if row > 0 {
+            let cells = (0..row).rev().map(|r| r * cols + col);
+            self.redirect(cells, Heading::South, cell - cols, &mut overwritten);
+        }
+        if row + 1 < self.rows {
+            let cells = (row + 1..self.rows).map(|r| r * cols + col);
+            self.redirect(cells, Heading::North, cell + cols, &mut overwritten);
+        }
+        if col > 0 {
+            let cells = (0..col).rev().map(|c| row * cols + c);
+            self.redirect(cells, Heading::East, cell - 1, &mut overwritten);
+        }
+        if col + 1 < cols {
+            let cells = (col + 1..cols).map(|c| row * cols + c);
+            self.redirect(cells, Heading::West, cell + 1, &mut overwritten);
+        }
+
+        PlacedObstacle { cell, overwritten }
+    }
+
+    /// Points every cell walking towards `direction`, up to the first rock,
+    /// at `stop`.
+    fn redirect(
+        &mut self,
+        cells: impl Iterator<Item = usize>,
+        direction: Heading,
+        stop: usize,
+        overwritten: &mut Vec<(usize, Heading, u32)>,
+    ) {
+        for cell in cells.take_while(|&cell| !self.rocks[cell]) {
+            let entry = &mut self.stops[cell][direction.index()];
+            overwritten.push((cell, direction, *entry));
+            *entry = stop as u32;
+        }
+    }
+
+    pub fn remove_obstacle(&mut self, placed: PlacedObstacle) {
+        self.rocks[placed.cell] = false;
+        for (cell, direction, stop) in placed.overwritten {
+            self.stops[cell][direction.index()] = stop;
+        }
+    }
+
+    /// Walks the guard from `cell` until it leaves the map or repeats a turn.
+    /// `visited` is scratch space and is cleared first.
+    pub fn walk_loops(
+        &self,
+        mut cell: usize,
+        mut direction: Heading,
+        visited: &mut StateSet,
+    ) -> bool {
+        visited.clear();
+
+        while let Some(stop) = self.next_stop(cell, direction) {
+            direction = direction.turn_right();
+            cell = stop;
+
+            if !visited.insert(cell, direction) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// A bitset over every `(cell, direction)` state of the guard.
+#[derive(Clone, Debug)]
+pub struct StateSet(Vec<u64>);
+
+impl StateSet {
+    #[must_use]
+    pub fn new(cells: usize) -> Self {
+        StateSet(vec![0; (cells * 4).div_ceil(64)])
+    }
+
+    pub fn clear(&mut self) {
+        self.0.fill(0);
+    }
+
+    /// Returns whether the state was newly added.
+    pub fn insert(&mut self, cell: usize, direction: Heading) -> bool {
+        let bit = cell * 4 + direction.index();
+        let (word, mask) = (bit / 64, 1 << (bit % 64));
+        let fresh = self.0[word] & mask == 0;
+        self.0[word] |= mask;
+
+        fresh
+    }
+}
+
+/// Every position the guard steps on before leaving the map, including where
+/// it starts. The puzzle promises the unmodified map doesn't loop.
+pub fn patrolled_positions(world: &World) -> HashSet<Position> {
+    let mut world = world.clone();
+    let mut distinct = HashSet::from([world.guard_loc]);
+
+    loop {
+        let start = world.guard_loc;
+        let res = world.walk_guard();
+        let guard_pos = match res {
+            Ok(pos) => pos,
+            Err(pos) => pos,
+        };
+
+        distinct.extend(start.to(&guard_pos));
+
+        if res.is_err() {
+            break;
+        }
+    }
+
+    distinct
+}
+
+/// Counts the single obstacles that would trap the guard in a loop.
+///
+/// Only positions on the guard's original patrol can change its route, and
+/// each one is checked in parallel against its own copy of the jump table.
+pub fn count_loop_obstacles(world: &World) -> usize {
+    let table = JumpTable::new(world);
+    let start = table.cell(&world.guard_loc);
+    let candidates = patrolled_positions(world)
+        .into_iter()
+        .filter(|&position| position != world.guard_loc)
+        .collect::<Vec<_>>();
+
+    candidates
+        .into_par_iter()
+        .map_init(
+            || (table.clone(), StateSet::new(table.rows * table.cols)),
+            |(table, visited), candidate| {
+                let placed = table.place_obstacle(candidate);
+                let loops = table.walk_loops(start, world.guard_direction, visited);
+                table.remove_obstacle(placed);
+
+                loops
+            },
+        )
+        .filter(|&loops| loops)
+        .count()
+}
+
+/// The original part 2 search: clone the whole world for every candidate and
+/// walk it rock by rock. Kept as a reference for [`count_loop_obstacles`].
+pub fn count_loop_obstacles_rewalk(world: &World) -> usize {
+    let mut count = 0;
+    for candidate in patrolled_positions(world) {
+        let mut distinct = HashSet::<(Position, Heading)>::new();
+
+        let mut world = world.clone();
+        if world.place_obstacle(candidate).is_err() {
+            continue;
+        }
+
+        loop {
+            let res = world.walk_guard();
+
+            // walked off the edge, no loop.
+            if res.is_err() {
+                break;
+            }
+
+            // this indicates a loop!
+            if !distinct.insert((*world.guard_location(), *world.guard_direction())) {
+                count += 1;
+                break;
+            }
+        }
+    }
+
+    count
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day6::{ColIdx, Direction, GridItem::*, RowIdx, World};
-    use std::sync::LazyLock;
+    use crate::day6::{ColIdx, Heading, GridItem::*, RowIdx, World};
+    use itertools::Itertools;
+    use std::{collections::HashSet, sync::LazyLock};
+    use test_case::test_case;
+
+    use super::{
+        count_loop_obstacles, count_loop_obstacles_rewalk, patrolled_positions, JumpTable,
+    };
 
     use super::{parser::world, Position};
 
@@ -273,7 +623,16 @@ mod test {
                 Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty,
             ],
             vec![
-                Empty, Rock, Empty, Empty, Guard, Empty, Empty, Empty, Empty, Empty,
+                Empty,
+                Rock,
+                Empty,
+                Empty,
+                Guard(super::Guard::new(Heading::North)),
+                Empty,
+                Empty,
+                Empty,
+                Empty,
+                Empty,
             ],
             vec![
                 Empty, Empty, Empty, Empty, Empty, Empty, Empty, Empty, Rock, Empty,
@@ -287,9 +646,167 @@ mod test {
         ],
         grid_size: (10, 10),
         guard_loc: Position::from((RowIdx(6), ColIdx(4))),
-        guard_direction: Direction::North,
+        guard_direction: Heading::North,
     });
 
+    const SAMPLE_INPUT: &str = r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+"#;
+
+    #[test]
+    fn test_jump_table_stops() {
+        let table = JumpTable::new(&SAMPLE);
+        let start = table.cell(&Position::from((RowIdx(6), ColIdx(4))));
+
+        let stop = table.next_stop(start, Heading::North).unwrap();
+        assert_eq!(table.position(stop), Position::from((RowIdx(1), ColIdx(4))));
+
+        let stop = table.next_stop(stop, Heading::East).unwrap();
+        assert_eq!(table.position(stop), Position::from((RowIdx(1), ColIdx(8))));
+
+        assert_eq!(table.next_stop(start, Heading::South), None);
+    }
+
+    #[test]
+    fn test_jump_table_obstacle_round_trip() {
+        let fresh = JumpTable::new(&SAMPLE);
+        let mut table = fresh.clone();
+        let obstacle = Position::from((RowIdx(6), ColIdx(3)));
+
+        let placed = table.place_obstacle(obstacle);
+        let mut world = SAMPLE.clone();
+        world.place_obstacle(obstacle).unwrap();
+        assert_eq!(table.stops, JumpTable::new(&world).stops);
+
+        table.remove_obstacle(placed);
+        assert_eq!(table.stops, fresh.stops);
+        assert_eq!(table.rocks, fresh.rocks);
+    }
+
+    #[test]
+    fn test_count_loop_obstacles() {
+        assert_eq!(count_loop_obstacles(&SAMPLE), 6);
+        assert_eq!(count_loop_obstacles_rewalk(&SAMPLE), 6);
+    }
+
+    #[test]
+    fn test_count_loop_obstacles_matches_rewalk() {
+        // a scattering of rocks that's dense enough to produce a few loops
+        let mut seed = 0x2024_0006_u32;
+        let input = (0..40)
+            .map(|row| {
+                (0..40)
+                    .map(|col| {
+                        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                        match (row, col) {
+                            (20, 20) => '^',
+                            _ if (seed >> 16).is_multiple_of(12) => '#',
+                            _ => '.',
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .join("\n");
+        let (_, world) = world(&input).unwrap();
+
+        assert_eq!(count_loop_obstacles(&world), count_loop_obstacles_rewalk(&world));
+    }
+
+    #[test]
+    fn test_patrolled_positions() {
+        let (_, world) = world(SAMPLE_INPUT).unwrap();
+        assert_eq!(patrolled_positions(&world).len(), 41);
+    }
+
+    #[test]
+    fn test_trace_visits_patrolled_positions() {
+        let (_, world) = world(SAMPLE_INPUT).unwrap();
+        let trace = world.trace();
+
+        assert_eq!(
+            trace.first(),
+            Some(&(Position::from((RowIdx(6), ColIdx(4))), Heading::North))
+        );
+        assert_eq!(
+            trace.last(),
+            Some(&(Position::from((RowIdx(9), ColIdx(7))), Heading::South))
+        );
+        assert_eq!(
+            trace.iter().map(|(position, _)| *position).collect::<HashSet<_>>(),
+            patrolled_positions(&world)
+        );
+    }
+
+    #[test]
+    fn test_trace_turns_in_place() {
+        let (_, world) = world("#.\n^.\n").unwrap();
+
+        assert_eq!(
+            world.trace(),
+            vec![
+                (Position::from((RowIdx(1), ColIdx(0))), Heading::North),
+                (Position::from((RowIdx(1), ColIdx(0))), Heading::East),
+                (Position::from((RowIdx(1), ColIdx(1))), Heading::East),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_trace() {
+        let (_, world) = world(SAMPLE_INPUT).unwrap();
+        let expected = r#"....#.....
+....+---+#
+....|...|.
+..#.|...|.
+..+-+-+#|.
+..|.|.|.|.
+.#+-^-+-+.
+.+----++#.
+#+----+|..
+......#|..
+"#;
+
+        assert_eq!(world.render_trace(), expected);
+    }
+
+    #[test]
+    fn test_render_trace_loop() {
+        let (_, mut world) = world(SAMPLE_INPUT).unwrap();
+        world.place_obstacle(Position::from((RowIdx(6), ColIdx(3)))).unwrap();
+        let expected = r#"....#.....
+....+---+#
+....|...|.
+..#.|...|.
+....|..#|.
+....|...|.
+.#.#^---+.
+........#.
+#.........
+......#...
+"#;
+
+        assert_eq!(world.render_trace(), expected);
+    }
+
+    #[test_case(">", Heading::East)]
+    #[test_case("v", Heading::South)]
+    #[test_case("<", Heading::West)]
+    #[test_case("^", Heading::North)]
+    fn test_guard_direction_is_parsed(glyph: &str, expected: Heading) {
+        let (_, world) = world(&format!("..\n.{glyph}\n")).unwrap();
+
+        assert_eq!(*world.guard_direction(), expected);
+        assert_eq!(*world.guard_location(), Position::from((RowIdx(1), ColIdx(1))));
+    }
+
     #[test]
     fn test_next_rock_in_sight_north() {
         let expected = Ok(Position::from((RowIdx(0), ColIdx(4))));
@@ -313,7 +830,7 @@ mod test {
 "#;
 
         let (_, mut world) = world(input).unwrap();
-        world.guard_direction = Direction::South;
+        world.guard_direction = Heading::South;
 
         let expected = Ok(Position::from((RowIdx(7), ColIdx(8))));
         let actual = world.next_rock_in_sight();
@@ -336,7 +853,7 @@ mod test {
 "#;
 
         let (_, mut world) = world(input).unwrap();
-        world.guard_direction = Direction::West;
+        world.guard_direction = Heading::West;
 
         let expected = Ok(Position::from((RowIdx(6), ColIdx(1))));
         let actual = world.next_rock_in_sight();
@@ -359,7 +876,7 @@ mod test {
 "#;
 
         let (_, mut world) = world(input).unwrap();
-        world.guard_direction = Direction::East;
+        world.guard_direction = Heading::East;
 
         let expected = Ok(Position::from((RowIdx(4), ColIdx(7))));
         let actual = world.next_rock_in_sight();
@@ -382,7 +899,7 @@ mod test {
 "#;
 
         let (_, mut world) = world(input).unwrap();
-        world.guard_direction = Direction::South;
+        world.guard_direction = Heading::South;
 
         let expected = Err(Position::from((RowIdx(9), ColIdx(7))));
         let actual = world.next_rock_in_sight();
@@ -405,7 +922,7 @@ mod test {
 "#;
 
         let (_, mut world) = world(input).unwrap();
-        world.guard_direction = Direction::South;
+        world.guard_direction = Heading::South;
 
         let expected = Err(Position::from((RowIdx(9), ColIdx(7))));
         let actual = world.next_rock_in_sight();
@@ -415,43 +932,46 @@ mod test {
 }
 
 pub mod parser {
+    use aoc_parse::located_grid;
     use nom::{
         branch::alt,
         bytes::complete::tag,
-        character::complete::newline,
-        combinator::{map, value},
-        multi::{many1, separated_list0},
+        character::complete::anychar,
+        combinator::{map, map_opt, value},
         IResult,
     };
 
-    use super::{GridItem, World};
+    use super::{ColIdx, Guard, GridItem, Heading, Position, RowIdx, World};
 
     pub fn world(input: &str) -> IResult<&str, World> {
-        map(
-            separated_list0(newline, grid_row), 
-            |grid| World::new(grid)
+        map_opt(
+            located_grid(grid_item, |item| matches!(item, GridItem::Guard(_))),
+            |(grid, guards)| {
+                let &[(row, col)] = guards.as_slice() else { return None };
+                Some(World::new(grid.into_rows(), Position::from((RowIdx(row), ColIdx(col)))))
+            },
         )(input)
     }
 
-    fn grid_row(input: &str) -> IResult<&str, Vec<GridItem>> {
-        many1(grid_item)(input)
-    }
-
     fn grid_item(input: &str) -> IResult<&str, GridItem> {
         alt((
             value(GridItem::Empty, tag(".")),
             value(GridItem::Rock, tag("#")),
-            value(GridItem::Guard, tag("^")),
+            map(guard, GridItem::Guard),
         ))(input)
     }
 
+    fn guard(input: &str) -> IResult<&str, Guard> {
+        map(map_opt(anychar, Heading::from_arrow), Guard::new)(input)
+    }
+
     #[cfg(test)]
     mod test {
 
-        use crate::day6::{parser::world, test::SAMPLE, GridItem};
+        use crate::day6::{parser::world, test::SAMPLE, ColIdx, Heading, Guard, GridItem, RowIdx};
         use test_case::test_case;
 
-        use super::{grid_item, grid_row};
+        use super::grid_item;
 
         #[test]
         fn test_parse_sample_input() {
@@ -476,22 +996,36 @@ pub mod parser {
             assert_eq!(actual, *SAMPLE);
         }
 
+        #[test]
+        fn test_parse_crlf_and_guards() {
+            let input = "..#\r\n.^.\r\n...\r\n";
+            let (_, actual) = world(input).unwrap();
+            assert_eq!(actual, world(&input.replace('\r', "")).unwrap().1);
+            assert_eq!(actual.guard_loc, (RowIdx(1), ColIdx(1)).into());
+
+            assert!(world("..#\n...\n").is_err());
+            assert!(world("^.#\n..<\n").is_err());
+        }
+
         #[test]
         fn parse_grid_line() {
             use crate::day6::GridItem::*;
 
             let input = "....#.....";
-            let (_, actual) = grid_row(input).unwrap();
-            let expected = vec![
+            let (_, actual) = aoc_parse::grid(grid_item)(input).unwrap();
+            let expected = [
                 Empty, Empty, Empty, Empty, Rock, Empty, Empty, Empty, Empty, Empty,
             ];
 
-            assert_eq!(actual, expected);
+            assert_eq!(actual.cells(), expected);
         }
 
         #[test_case(".", GridItem::Empty)]
         #[test_case("#", GridItem::Rock)]
-        #[test_case("^", GridItem::Guard)]
+        #[test_case("^", GridItem::Guard(Guard::new(Heading::North)))]
+        #[test_case(">", GridItem::Guard(Guard::new(Heading::East)))]
+        #[test_case("v", GridItem::Guard(Guard::new(Heading::South)))]
+        #[test_case("<", GridItem::Guard(Guard::new(Heading::West)))]
         fn test_parse_griditem(input: &str, expected: GridItem) {
             let (_, actual) = grid_item(input).unwrap();
             assert_eq!(actual, expected);
@@ -1,24 +1,419 @@
-#[derive(Clone, Copy, Debug)]
-pub enum Token {
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token<O = Operator> {
     Output(u64),
     Operand(u64),
-    Operation(fn(u64, u64) -> u64)
+    Operation(O),
+}
+
+impl<O: Operation> Display for Token<O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Output(value) | Token::Operand(value) => write!(f, "{value}"),
+            Token::Operation(operation) => write!(f, "{}", operation.symbol()),
+        }
+    }
+}
+
+/// A binary operator the solver can run in reverse.
+///
+/// `apply` returns `None` on overflow. `unapply` answers "which `lhs` gives
+/// `lhs op rhs == target`?", returning `None` when no such `lhs` exists.
+/// Implement this for your own type to search with operators beyond
+/// [`Operator`].
+pub trait Operation: Copy {
+    fn symbol(&self) -> &'static str;
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64>;
+    fn unapply(&self, target: u64, rhs: u64) -> Option<Lhs>;
+}
+
+/// The left hand side [`Operation::unapply`] found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lhs {
+    Exactly(u64),
+    /// Every `lhs` would do, like for `lhs * 0 == 0`.
+    Any,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Add,
+    Mul,
+    Concat,
+}
+
+impl Operator {
+    pub const PART_1: [Operator; 2] = [Operator::Add, Operator::Mul];
+    pub const PART_2: [Operator; 3] = [Operator::Add, Operator::Mul, Operator::Concat];
+}
+
+impl Operation for Operator {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Operator::Add => "+",
+            Operator::Mul => "*",
+            Operator::Concat => "||",
+        }
+    }
+
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        match self {
+            Operator::Add => lhs.checked_add(rhs),
+            Operator::Mul => lhs.checked_mul(rhs),
+            Operator::Concat => lhs
+                .checked_mul(decimal_shift(rhs))
+                .and_then(|shifted| shifted.checked_add(rhs)),
+        }
+    }
+
+    fn unapply(&self, target: u64, rhs: u64) -> Option<Lhs> {
+        match self {
+            Operator::Add => target.checked_sub(rhs).map(Lhs::Exactly),
+            Operator::Mul if rhs == 0 => (target == 0).then_some(Lhs::Any),
+            Operator::Mul => target.is_multiple_of(rhs).then(|| Lhs::Exactly(target / rhs)),
+            Operator::Concat => {
+                let shift = decimal_shift(rhs);
+                (target % shift == rhs).then(|| Lhs::Exactly(target / shift))
+            }
+        }
+    }
+}
+
+/// The power of ten that shifts a number left by as many digits as `n` has.
+fn decimal_shift(n: u64) -> u64 {
+    10u64.pow(n.checked_ilog10().unwrap_or(0) + 1)
+}
+
+/// Finds operators which, placed between `operands` and evaluated left to
+/// right, produce `target`.
+///
+/// Works backwards from the target, peeling off the last operand with every
+/// operator that can be undone, so impossible branches die as soon as a
+/// division leaves a remainder or a concatenation's suffix doesn't match.
+pub fn solve<O: Operation>(target: u64, operands: &[u64], operators: &[O]) -> Option<Vec<O>> {
+    match operands {
+        [] => None,
+        [first] => (*first == target).then(Vec::new),
+        [rest @ .., last] => operators.iter().find_map(|operator| {
+            let mut solution = match operator.unapply(target, *last)? {
+                Lhs::Exactly(lhs) => solve(lhs, rest, operators)?,
+                Lhs::Any => solve_any(rest, operators)?,
+            };
+            solution.push(*operator);
+            Some(solution)
+        }),
+    }
+}
+
+/// Finds operators that make `operands` evaluate to anything at all, which
+/// only fails when every choice overflows.
+fn solve_any<O: Operation>(operands: &[u64], operators: &[O]) -> Option<Vec<O>> {
+    fn extend<O: Operation>(
+        acc: u64,
+        operands: &[u64],
+        operators: &[O],
+        chosen: &mut Vec<O>,
+    ) -> bool {
+        let Some((&next, rest)) = operands.split_first() else {
+            return true;
+        };
+
+        operators.iter().any(|operator| {
+            let Some(value) = operator.apply(acc, next) else {
+                return false;
+            };
+            chosen.push(*operator);
+            if extend(value, rest, operators, chosen) {
+                return true;
+            }
+            chosen.pop();
+            false
+        })
+    }
+
+    let (&first, rest) = operands.split_first()?;
+    let mut chosen = Vec::new();
+
+    extend(first, rest, operators, &mut chosen).then_some(chosen)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    /// The operator at this index (0 being between the first two operands)
+    /// overflowed a `u64`.
+    Overflow { operator_idx: usize },
+}
+
+/// An output, its operands and the operators placed between them, e.g.
+/// `190 = 10 * 19`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Equation<O = Operator> {
+    output: u64,
+    operands: Vec<u64>,
+    operators: Vec<O>,
+}
+
+impl<O: Operation> Equation<O> {
+    #[must_use]
+    pub fn new(output: u64, operands: Vec<u64>, operators: Vec<O>) -> Self {
+        assert!(
+            operators.len() + 1 == operands.len(),
+            "an equation needs exactly one operator between each pair of operands"
+        );
+
+        Equation {
+            output,
+            operands,
+            operators,
+        }
+    }
+
+    pub fn output(&self) -> u64 {
+        self.output
+    }
+
+    pub fn operators(&self) -> &[O] {
+        &self.operators
+    }
+
+    /// The output followed by the right hand side, operands interleaved with
+    /// operations.
+    pub fn tokens(&self) -> impl Iterator<Item = Token<O>> + '_ {
+        std::iter::once(Token::Output(self.output))
+            .chain(std::iter::once(Token::Operand(self.operands[0])))
+            .chain(self.operators.iter().zip(&self.operands[1..]).flat_map(
+                |(&operator, &operand)| [Token::Operation(operator), Token::Operand(operand)],
+            ))
+    }
+
+    /// Evaluates the right hand side strictly left to right.
+    pub fn evaluate(&self) -> Result<u64, EvalError> {
+        self.operators
+            .iter()
+            .zip(&self.operands[1..])
+            .enumerate()
+            .try_fold(self.operands[0], |acc, (operator_idx, (operator, &rhs))| {
+                operator
+                    .apply(acc, rhs)
+                    .ok_or(EvalError::Overflow { operator_idx })
+            })
+    }
+
+    pub fn holds(&self) -> bool {
+        self.evaluate() == Ok(self.output)
+    }
+}
+
+impl<O: Operation> Display for Equation<O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut tokens = self.tokens();
+        if let Some(output) = tokens.next() {
+            write!(f, "{output} =")?;
+        }
+        for token in tokens {
+            write!(f, " {token}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Every equation that can be made true, with the operators that do it.
+pub fn solved_equations<'a, O: Operation>(
+    equations: &'a [(u64, Vec<u64>)],
+    operators: &'a [O],
+) -> impl Iterator<Item = Equation<O>> + 'a {
+    equations.iter().filter_map(move |(output, operands)| {
+        solve(*output, operands, operators)
+            .map(|solution| Equation::new(*output, operands.clone(), solution))
+    })
+}
+
+/// Sum of the test values of every equation that can be made true.
+pub fn total_calibration<O: Operation>(equations: &[(u64, Vec<u64>)], operators: &[O]) -> u64 {
+    solved_equations(equations, operators)
+        .map(|equation| equation.output())
+        .sum()
 }
 
 #[cfg(test)]
 mod test {
+    use test_case::test_case;
 
+    use super::{
+        decimal_shift, parser, solve, solved_equations, total_calibration, Equation, EvalError,
+        Lhs, Operation, Operator, Token,
+    };
+
+    const SAMPLE: &str = r#"190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20
+"#;
+
+    #[test_case(0, 10)]
+    #[test_case(9, 10)]
+    #[test_case(10, 100)]
+    #[test_case(345, 1000)]
+    fn test_decimal_shift(n: u64, expected: u64) {
+        assert_eq!(decimal_shift(n), expected);
+    }
+
+    #[test_case(Operator::Add, 12, 345)]
+    #[test_case(Operator::Mul, 12, 345)]
+    #[test_case(Operator::Concat, 12, 345)]
+    #[test_case(Operator::Concat, 7, 0)]
+    fn test_unapply_inverts_apply(operator: Operator, lhs: u64, rhs: u64) {
+        let target = operator.apply(lhs, rhs).unwrap();
+        assert_eq!(operator.unapply(target, rhs), Some(Lhs::Exactly(lhs)));
+    }
+
+    #[test_case(Operator::Add, 5, 6)]
+    #[test_case(Operator::Mul, 7, 2)]
+    #[test_case(Operator::Mul, 7, 0)]
+    #[test_case(Operator::Concat, 156, 5)]
+    fn test_unapply_rejects_impossible(operator: Operator, target: u64, rhs: u64) {
+        assert_eq!(operator.unapply(target, rhs), None);
+    }
+
+    #[test]
+    fn test_unapply_multiply_by_zero() {
+        assert_eq!(Operator::Mul.unapply(0, 0), Some(Lhs::Any));
+        assert_eq!(Operator::Add.unapply(0, 0), Some(Lhs::Exactly(0)));
+    }
+
+    #[test_case(Operator::Add, u64::MAX, 1)]
+    #[test_case(Operator::Mul, u64::MAX, 2)]
+    #[test_case(Operator::Concat, u64::MAX, 1)]
+    fn test_apply_detects_overflow(operator: Operator, lhs: u64, rhs: u64) {
+        assert_eq!(operator.apply(lhs, rhs), None);
+    }
+
+    #[test_case(190, vec![10, 19], &Operator::PART_1, Some(vec![Operator::Mul]))]
+    #[test_case(3267, vec![81, 40, 27], &Operator::PART_1, Some(vec![Operator::Mul, Operator::Add]))]
+    #[test_case(83, vec![17, 5], &Operator::PART_1, None)]
+    #[test_case(156, vec![15, 6], &Operator::PART_2, Some(vec![Operator::Concat]))]
+    #[test_case(7290, vec![6, 8, 6, 15], &Operator::PART_2, Some(vec![Operator::Mul, Operator::Concat, Operator::Mul]))]
+    #[test_case(5, vec![5], &Operator::PART_1, Some(vec![]))]
+    #[test_case(0, vec![5, 0], &Operator::PART_1, Some(vec![Operator::Mul]))]
+    #[test_case(0, vec![5, 0, 3], &Operator::PART_1, Some(vec![Operator::Mul, Operator::Mul]))]
+    #[test_case(0, vec![7, 2, 0], &Operator::PART_1, Some(vec![Operator::Add, Operator::Mul]))]
+    #[test_case(0, vec![u64::MAX, 2, 0], &Operator::PART_1, None; "prefix always overflows")]
+    fn test_solve(
+        target: u64,
+        operands: Vec<u64>,
+        operators: &[Operator],
+        expected: Option<Vec<Operator>>,
+    ) {
+        assert_eq!(solve(target, &operands, operators), expected);
+    }
+
+    #[test]
+    fn test_solutions_evaluate_to_target() {
+        let (_, equations) = parser::parse_lines(SAMPLE).unwrap();
+        for equation in solved_equations(&equations, &Operator::PART_2) {
+            assert!(equation.holds(), "{equation}");
+        }
+    }
+
+    #[test]
+    fn test_display_solved_sample() {
+        let (_, equations) = parser::parse_lines(SAMPLE).unwrap();
+        let rendered = solved_equations(&equations, &Operator::PART_2)
+            .map(|equation| equation.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            rendered,
+            vec![
+                "190 = 10 * 19",
+                "3267 = 81 * 40 + 27",
+                "156 = 15 || 6",
+                "7290 = 6 * 8 || 6 * 15",
+                "192 = 17 || 8 + 14",
+                "292 = 11 + 6 * 16 + 20",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokens() {
+        let equation = Equation::new(190, vec![10, 19], vec![Operator::Mul]);
+
+        assert_eq!(
+            equation.tokens().collect::<Vec<_>>(),
+            vec![
+                Token::Output(190),
+                Token::Operand(10),
+                Token::Operation(Operator::Mul),
+                Token::Operand(19),
+            ]
+        );
+    }
+
+    #[test_case(vec![2, 3, 4], vec![Operator::Add, Operator::Mul], Ok(20))]
+    #[test_case(vec![12, 345], vec![Operator::Concat], Ok(12345))]
+    #[test_case(vec![1, u64::MAX, 2], vec![Operator::Mul, Operator::Add], Err(EvalError::Overflow { operator_idx: 1 }))]
+    #[test_case(vec![u64::MAX, 2, 0], vec![Operator::Mul, Operator::Mul], Err(EvalError::Overflow { operator_idx: 0 }))]
+    fn test_evaluate(
+        operands: Vec<u64>,
+        operators: Vec<Operator>,
+        expected: Result<u64, EvalError>,
+    ) {
+        assert_eq!(Equation::new(0, operands, operators).evaluate(), expected);
+    }
+
+    #[test]
+    fn test_custom_operation() {
+        #[derive(Clone, Copy)]
+        struct Sub;
+
+        impl Operation for Sub {
+            fn symbol(&self) -> &'static str {
+                "-"
+            }
+
+            fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+                lhs.checked_sub(rhs)
+            }
+
+            fn unapply(&self, target: u64, rhs: u64) -> Option<Lhs> {
+                target.checked_add(rhs).map(Lhs::Exactly)
+            }
+        }
+
+        let (_, equations) = parser::parse_lines("5: 9 4\n6: 9 4\n").unwrap();
+        let rendered = solved_equations(&equations, &[Sub])
+            .map(|equation| equation.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(rendered, vec!["5 = 9 - 4"]);
+    }
+
+    #[test_case(&Operator::PART_1, 3749)]
+    #[test_case(&Operator::PART_2, 11387)]
+    fn test_total_calibration(operators: &[Operator], expected: u64) {
+        let (_, equations) = parser::parse_lines(SAMPLE).unwrap();
+        assert_eq!(total_calibration(&equations, operators), expected);
+    }
 }
 
 pub mod parser {
-    use nom::{bytes::complete::tag, character::complete::{newline, space1, u64}, multi::separated_list1, sequence::separated_pair, IResult};
+    use aoc_parse::{key_values, lines, unsigned};
+    use nom::IResult;
 
     pub fn parse_lines(input: &str) -> IResult<&str, Vec<(u64, Vec<u64>)>> {
-        separated_list1(newline, parse_line)(input)
+        lines(parse_line)(input)
     }
 
     fn parse_line(input: &str) -> IResult<&str, (u64, Vec<u64>)> {
-        separated_pair(u64, tag(": "), separated_list1(space1, u64))(input)
+        key_values(unsigned, unsigned)(input)
     }
 
     #[cfg(test)]
@@ -38,7 +433,7 @@ pub mod parser {
                 (161011, vec![16, 10, 13]),
                 (192, vec![17, 8, 14]),
                 (21037, vec![9, 7, 18, 13]),
-                (292, vec![11, 6, 16, 20])
+                (292, vec![11, 6, 16, 20]),
             ];
 
             let input = r#"190: 10 19
@@ -62,4 +457,4 @@ pub mod parser {
             assert_eq!(actual, expected);
         }
     }
-}
\ No newline at end of file
+}
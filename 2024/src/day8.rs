@@ -1,242 +1,272 @@
-use std::ops::{Add, Mul, Sub};
+use std::collections::{HashMap, HashSet};
+
+use aoc_geometry::Vec2;
+use aoc_math::gcd;
+use itertools::Itertools;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq,)]
 pub struct PointPair(pub Point, pub Point);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct Point {
-    x: i64,
-    y: i64
-}
-
-impl Point {
-    pub fn x(&self) -> i64 {
-        self.x
-    }
+/// A spot on the map, `x` being the column and `y` the row.
+pub type Point = Vec2<i64>;
 
-    pub fn y(&self) -> i64 {
-        self.y
-    }
+/// The width and height of the map, antinodes outside of it don't count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub width: i64,
+    pub height: i64,
 }
 
-impl From<(RowIdx, ColIdx)> for Point {
-    fn from(source: (RowIdx, ColIdx)) -> Self {
-        Self {
-            x: source.1.0,
-            y: source.0.0,
-        }
+impl Bounds {
+    pub fn contains(&self, point: &Point) -> bool {
+        (0..self.width).contains(&point.x) && (0..self.height).contains(&point.y)
     }
 }
 
-impl From<(X, Y)> for Point {
-    fn from(value: (X, Y)) -> Self {
-        Self {
-            x: value.0.0.round() as i64,
-            y: value.1.0.round() as i64,
-        }
-    }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AntinodeMode {
+    /// One antinode on either side of the pair, as far out as the antennas
+    /// are apart.
+    TwoAntinodes,
+    /// Every grid point in line with the pair, antennas included.
+    ResonantHarmonics,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
-struct X(f64);
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
-struct Y(f64);
+impl PointPair {
+    /// Antinodes of the two antennas that land inside `bounds`.
+    ///
+    /// Everything stays on the integer lattice: with harmonics the line is
+    /// walked in steps of the difference between the antennas divided by its
+    /// gcd, which is the smallest step that lands exactly on grid points.
+    pub fn antinodes(&self, bounds: Bounds, mode: AntinodeMode) -> Vec<Point> {
+        let delta = self.1 - self.0;
 
-impl Sub for X {
-    type Output = f64;
+        match mode {
+            AntinodeMode::TwoAntinodes => [self.0 - delta, self.1 + delta]
+            .into_iter()
+            .filter(|point| bounds.contains(point))
+            .collect(),
+            AntinodeMode::ResonantHarmonics => {
+                let divisor = gcd(delta.x, delta.y);
+                if divisor == 0 {
+                    // both antennas on the same spot, there's no line
+                    return bounds.contains(&self.0).then_some(self.0).into_iter().collect();
+                }
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        self.0 - rhs.0
-    }
-}
+                let step = Vec2::new(delta.x / divisor, delta.y / divisor);
+                let walk = |sign: i64| {
+                    (0..)
+                        .map(move |mult| self.0 + step * (sign * mult))
+                        .take_while(|point| bounds.contains(point))
+                };
 
-impl Mul<f64> for X {
-    type Output = f64;
-    
-    fn mul(self, rhs: f64) -> Self::Output {
-        self.0 * rhs
+                walk(-1).chain(walk(1).skip(1)).collect()
+            }
+        }
     }
 }
 
-impl Sub for Y {
-    type Output = f64;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        self.0 - rhs.0
-    }
+/// Antenna positions grouped by frequency, along with the size of the map
+/// they were read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AntennaMap {
+    antennas: HashMap<char, HashSet<Point>>,
+    bounds: Bounds,
 }
 
-impl Add<f64> for Y {
-    type Output = f64;
-    
-    fn add(self, rhs: f64) -> Self::Output {
-        self.0 + rhs
+impl AntennaMap {
+    pub fn antennas(&self) -> &HashMap<char, HashSet<Point>> {
+        &self.antennas
     }
-}
 
-impl PointPair {
-    /// give two antenna of the same variant they create antinodes
-    /// at locations along the line the create, on the outside of the
-    /// antenna pair, each antinode at a distance equal to the distance
-    /// between the antenna pair.
-    /// 
-    /// to find antinodes we can find the line defined by each antenna point
-    /// find the distance (*d*) between the antenna pair
-    /// for each antenna, draw a circle with radius *d* around the point
-    /// and where that circle intersects the line is where there's an antinode
-    pub fn antinodes(&self, grid_size: (i64, i64)) -> Vec<Point> {
-        let (x_0, y_0) = (X(self.0.x as f64), Y(self.0.y as f64));
-        let (x_1, y_1) = (X(self.1.x as f64), Y(self.1.y as f64));
-
-        let d = ((x_1 - x_0).powi(2) + (y_1 - y_0).powi(2)).sqrt();
-
-        if self.0.x == self.1.x {
-            return (1..).map(|mult| {
-                Point {
-                    x: self.0.x,
-                    y: self.0.y.max(self.1.y) - ((d as i64) * mult)
-                }
-            })
-            .take_while(|point| point.x() >= 0 && point.y() >= 0 && point.x() < grid_size.0 && point.y() < grid_size.1)
-            .inspect(|p| {
-                println!("{self:?} {p:?}");
-            })
-            .chain((1..).map(|mult| {
-                Point {
-                    x: self.0.x,
-                    y: self.0.y.min(self.1.y) + ((d as i64) * mult)
-                }
-            })
-            .take_while(|point| point.x() >= 0 && point.y() >= 0 && point.x() < grid_size.0 && point.y() < grid_size.1))
-            .inspect(|p| {
-                println!("{self:?} {p:?}");
-            })
-            .collect() 
-        // special case horizontal line just because it's easy
-        } else if self.0.y == self.1.y {
-            return (1..).map(|mult| {
-                Point {
-                    x: self.0.x.max(self.1.x) - ((d as i64) * mult),
-                    y: self.0.y
-                }
-            })
-            .take_while(|point| point.x() >= 0 && point.y() >= 0 && point.x() < grid_size.0 && point.y() < grid_size.1)
-            .inspect(|p| {
-                println!("{self:?} {p:?}");
-            })
-            .chain((1..).map(|mult| {
-                Point {
-                    x: self.0.x.min(self.1.x) + ((d as i64) * mult),
-                    y: self.0.y
-                }
-            })
-            .take_while(|point| point.x() >= 0 && point.y() >= 0 && point.x() < grid_size.0 && point.y() < grid_size.1))
-            .inspect(|p| {
-                println!("{self:?} {p:?}");
-            })
-            .collect();
-        }
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
 
-        let slope = (y_1 - y_0) / (x_1 - x_0);
-        let y_for_x_on_line = |x: X| Y(y_0 + (slope * (x - x_0)));
+    /// Every distinct antinode made by a pair of same-frequency antennas.
+    pub fn antinodes(&self, mode: AntinodeMode) -> HashSet<Point> {
+        self.antennas
+            .values()
+            .flat_map(|points| points.iter().tuple_combinations())
+            .map(|(a, b)| PointPair(*a, *b))
+            .flat_map(|pair| pair.antinodes(self.bounds, mode))
+            .collect()
+    }
 
-        let xs_d_from_pt = |d: f64, x0: X| {
-            let offset = d / (1.0 + slope.powi(2)).sqrt();
-            (X(x0.0 - offset), X(x0.0 + offset))
-        };
+    /// The antenna map with `antinodes` drawn on as `#`. Antennas are drawn
+    /// over antinodes that share their spot.
+    pub fn render(&self, antinodes: &HashSet<Point>) -> String {
+        let mut grid = vec![vec!['.'; self.bounds.width as usize]; self.bounds.height as usize];
 
-        let (lesser, greater) = if x_0 < x_1 { 
-            (x_0, x_1)
-        } else {
-             (x_1, x_0)
-        };
+        for point in antinodes.iter().filter(|point| self.bounds.contains(point)) {
+            grid[point.y as usize][point.x as usize] = '#';
+        }
 
-        (1..).map(|mult| {
-            let right = xs_d_from_pt(d * (mult as f64), lesser).1;
-            Point {
-                x: right.0 as i64,
-                y: y_for_x_on_line(right).0 as i64
+        for (&frequency, points) in &self.antennas {
+            for point in points {
+                grid[point.y as usize][point.x as usize] = frequency;
             }
-        })
-        .take_while(|point| point.x() >= 0 && point.y() >= 0 && point.x() < grid_size.0 && point.y() < grid_size.1)
-        .inspect(|p| {
-                println!("{self:?} {p:?}");
-            })
-        .chain((1..).map(|mult| {
-            let left = xs_d_from_pt(d * (mult as f64), greater).0;
-            Point {
-                x: left.0 as i64,
-                y: y_for_x_on_line(left).0 as i64
-            }
-        })
-        .take_while(|point| point.x() >= 0 && point.y() >= 0 && point.x() < grid_size.0 && point.y() < grid_size.1))
-        .inspect(|p| {
-                println!("{self:?} {p:?}");
-            })
-        .collect()
+        }
+
+        grid.into_iter()
+            .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+            .collect()
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct RowIdx(i64);
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct ColIdx(i64);
-
 #[cfg(test)]
 mod test {
     use test_case::test_case;
 
-    use super::{PointPair, Point, ColIdx, RowIdx};
+    use super::{parser, AntinodeMode, Bounds, PointPair, Point};
+
+    const SAMPLE: &str = r#"............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............
+"#;
+
+    const BOUNDS: Bounds = Bounds { width: 12, height: 12 };
 
     #[test_case(
         PointPair(
-            (RowIdx(3), ColIdx(5)).into(),
-            (RowIdx(5), ColIdx(5)).into()
+            Point::new(5, 3),
+            Point::new(5, 5)
         ),
-        [(RowIdx(1), ColIdx(5)).into(), (RowIdx(7), ColIdx(5)).into()]
+        [Point::new(5, 1), Point::new(5, 7)]
     )]
     #[test_case(
         PointPair(
-            (RowIdx(5), ColIdx(4)).into(),
-            (RowIdx(5), ColIdx(5)).into()
+            Point::new(4, 5),
+            Point::new(5, 5)
         ),
-        [(RowIdx(5), ColIdx(3)).into(), (RowIdx(5), ColIdx(6)).into()]
+        [Point::new(3, 5), Point::new(6, 5)]
     )]
     #[test_case(
         PointPair(
-            (RowIdx(3), ColIdx(4)).into(),
-            (RowIdx(5), ColIdx(5)).into(),
+            Point::new(4, 3),
+            Point::new(5, 5),
         ),
-        [(RowIdx(1), ColIdx(3)).into(), (RowIdx(7), ColIdx(6)).into()]
+        [Point::new(3, 1), Point::new(6, 7)]
     )]
     #[test_case(
         PointPair(
-            (RowIdx(4), ColIdx(8)).into(),
-            (RowIdx(5), ColIdx(5)).into(),
+            Point::new(8, 4),
+            Point::new(5, 5),
         ),
-        [(RowIdx(3), ColIdx(11)).into(), (RowIdx(6), ColIdx(2)).into()]
+        [Point::new(11, 3), Point::new(2, 6)]
     )]
     fn test_antinodes(pair: PointPair, expected: [Point;2 ]) {
-        assert_eq!(pair.antinodes((12, 12)), expected);
+        assert_eq!(pair.antinodes(BOUNDS, AntinodeMode::TwoAntinodes), expected);
+    }
+
+    #[test]
+    fn test_antinodes_off_the_map_are_dropped() {
+        let pair = PointPair(Point::new(0, 0), Point::new(2, 1));
+        assert_eq!(
+            pair.antinodes(BOUNDS, AntinodeMode::TwoAntinodes),
+            vec![Point::new(4, 2)]
+        );
+    }
+
+    #[test]
+    fn test_harmonics_use_reduced_step() {
+        // (0, 0) -> (4, 2) passes through (2, 1) on the way
+        let pair = PointPair(Point::new(0, 0), Point::new(4, 2));
+        let bounds = Bounds { width: 7, height: 4 };
+
+        assert_eq!(
+            pair.antinodes(bounds, AntinodeMode::ResonantHarmonics),
+            vec![
+                Point::new(0, 0),
+                Point::new(2, 1),
+                Point::new(4, 2),
+                Point::new(6, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_harmonics_in_both_directions() {
+        let pair = PointPair(Point::new(2, 2), Point::new(3, 2));
+        let bounds = Bounds { width: 5, height: 5 };
+
+        let mut actual = pair.antinodes(bounds, AntinodeMode::ResonantHarmonics);
+        actual.sort();
+
+        assert_eq!(
+            actual,
+            (0..5).map(|col| Point::new(col, 2)).collect::<Vec<Point>>()
+        );
+    }
+
+    #[test_case(AntinodeMode::TwoAntinodes, 14)]
+    #[test_case(AntinodeMode::ResonantHarmonics, 34)]
+    fn test_sample_antinode_count(mode: AntinodeMode, expected: usize) {
+        let map = parser::parse(SAMPLE);
+        assert_eq!(map.antinodes(mode).len(), expected);
+    }
+
+    #[test]
+    fn test_render() {
+        let map = parser::parse(SAMPLE);
+        let expected = r#"......#....#
+...#....0...
+....#0....#.
+..#....0....
+....0....#..
+.#....A.....
+...#........
+#......#....
+........A...
+.........A..
+..........#.
+..........#.
+"#;
+
+        assert_eq!(map.render(&map.antinodes(AntinodeMode::TwoAntinodes)), expected);
     }
 }
 
 
 pub mod parser {
     use std::collections::{HashMap, HashSet};
-    use super::{RowIdx, ColIdx, Point};
+    use super::{AntennaMap, Bounds, Point};
+
+    pub fn parse(input: &str) -> AntennaMap {
+        let mut lines = input.lines().collect::<Vec<_>>();
+        // blank lines after the map aren't rows, blank lines inside it are
+        let height = lines
+            .iter()
+            .rposition(|line| !line.trim().is_empty())
+            .map_or(0, |last| last + 1);
+        lines.truncate(height);
+
+        let bounds = Bounds {
+            width: lines.iter().map(|line| line.chars().count()).max().unwrap_or(0) as i64,
+            height: lines.len() as i64,
+        };
 
-    pub fn parse(input: &str) -> HashMap<char, HashSet<Point>> {
-        input.lines().enumerate()
-            .map(|(idx, line)| (RowIdx(idx as i64), line))
+        let antennas = lines.into_iter().enumerate()
+            .map(|(idx, line)| (idx as i64, line))
             .fold(HashMap::new(), |mut map, (row_idx, row)| {
                 for (col_idx, value) in row.chars().enumerate()
                     .filter(|(_, c)| c.is_alphanumeric()) {
-                        let set = map.entry(value).or_default();
-                        set.insert((row_idx, ColIdx(col_idx as i64)).into());
+                        let set: &mut HashSet<_> = map.entry(value).or_default();
+                        set.insert(Point::new(col_idx as i64, row_idx));
                     };
                 map
-            })
+            });
+
+        AntennaMap { antennas, bounds }
     }
 
     #[cfg(test)]
@@ -244,41 +274,56 @@ pub mod parser {
         use core::assert_eq;
         use std::collections::{HashMap, HashSet};
 
-        use crate::day8::{Point, ColIdx, RowIdx};
+        use crate::day8::{Bounds, Point};
 
         #[test]
         fn test_sample_input() {
-            let input = r#"
-   012345678901
-0  .........#..
-1  ##....#.0...
-2  .#.#.0..#...
-3  ..#..#.0..#.
-4  ..##0.......
-5  ....#.A..#..
-6  .#...##....#
-7  ...#..#.....
-8  #....#.#A...
-9 ...#.....#..
-10 ....#....#..
-11 .#........#."#;
+            let input = r#"............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............"#;
             let expected = HashMap::<char, HashSet<Point>>::from([
                 ('0', HashSet::from([
-                    (RowIdx(1), ColIdx(8)).into(),
-                    (RowIdx(2), ColIdx(5)).into(),
-                    (RowIdx(3), ColIdx(7)).into(),
-                    (RowIdx(4), ColIdx(4)).into(),
+                    Point::new(8, 1),
+                    Point::new(5, 2),
+                    Point::new(7, 3),
+                    Point::new(4, 4),
                 ])),
                 ('A', HashSet::from([
-                    (RowIdx(5), ColIdx(6)).into(),
-                    (RowIdx(8), ColIdx(8)).into(),
-                    (RowIdx(9), ColIdx(9)).into(),
+                    Point::new(6, 5),
+                    Point::new(8, 8),
+                    Point::new(9, 9),
                 ]))
             ]);
 
             let actual = super::parse(input);
-            assert_eq!(actual, expected);
+            assert_eq!(*actual.antennas(), expected);
+            assert_eq!(actual.bounds(), Bounds { width: 12, height: 12 });
+        }
+
+        #[test]
+        fn test_bounds_ignore_trailing_newline() {
+            let actual = super::parse("..a\n...\n");
+            assert_eq!(actual.bounds(), Bounds { width: 3, height: 2 });
+
+            let actual = super::parse("..a\n...\n\n  \n");
+            assert_eq!(actual.bounds(), Bounds { width: 3, height: 2 });
+        }
+
+        #[test]
+        fn test_blank_line_inside_map_is_a_row() {
+            let actual = super::parse("a..\n\n..a\n");
+
+            assert_eq!(actual.bounds(), Bounds { width: 3, height: 3 });
+            assert_eq!(actual.antennas()[&'a'], HashSet::from([Point::new(0, 0), Point::new(2, 2)]));
         }
-        
     }
-}
\ No newline at end of file
+}
@@ -1,181 +1,560 @@
-use std::fmt::Display;
+use std::{cmp::Reverse, collections::{BTreeMap, BinaryHeap, VecDeque}, fmt::Display};
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Disk(Vec<DiskItem>);
+/// A disk as a run-length list of segments, adjacent segments of the same
+/// kind are always merged.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Disk(Vec<Segment>);
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Segment {
+    Free {
+        len: usize,
+    },
+    File {
+        id: usize,
+        len: usize,
+    },
+}
+
+impl Segment {
+    pub fn len(&self) -> usize {
+        match self {
+            Segment::Free { len } | Segment::File { len, .. } => *len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Palette ids are drawn from when rendering, one character per block.
+const PALETTE: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderStyle {
+    /// One character per block. Ids past the end of the palette wrap around,
+    /// so id 62 looks just like id 0.
+    Palette,
+    /// Every file block as its full id in brackets, e.g. `[12]`.
+    Bracketed,
+}
+
+/// [`RenderStyle::Palette`] while every id has a character of its own,
+/// [`RenderStyle::Bracketed`] once they don't.
 impl Display for Disk {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for item in &self.0 {
-            write!(f, "{}", item)?;
-        }
+        let fits_palette = self.0.iter().all(|segment| match segment {
+            Segment::File { id, .. } => *id < PALETTE.len(),
+            Segment::Free { .. } => true,
+        });
+        let style = if fits_palette { RenderStyle::Palette } else { RenderStyle::Bracketed };
 
-        Ok(())
+        write!(f, "{}", self.render(style))
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compaction {
+    /// Single blocks from the end of the disk into the leftmost free block.
+    Blocks,
+    /// Whole files, highest id first, into the leftmost span that fits them.
+    Files,
+}
+
+/// `len` blocks of file `id` moving from block `from` to block `to`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub id: usize,
+    pub from: usize,
+    pub to: usize,
+    pub len: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    /// File `id` comes after file `expected`, the dense format can only hold
+    /// files in id order.
+    OutOfOrder { id: usize, expected: usize },
+    /// A segment longer than a single digit can describe.
+    TooLong { len: usize },
+}
+
 impl Disk {
-    pub fn defrag(&mut self) {
-        let empty_iter = self.0.iter().enumerate().filter(|(_, i)| matches!(i, DiskItem::Empty { size: _ })).map(|(idx, _)| idx).collect::<Vec<_>>().into_iter();
-        let item_iter = self.0.iter().enumerate().rev().filter(|(_, i)| matches!(i, DiskItem::Item { size: _, id: _ })).map(|(idx, _)| idx).collect::<Vec<_>>().into_iter();
+    pub fn from_segments(segments: impl IntoIterator<Item = Segment>) -> Self {
+        let mut disk = Disk::default();
+        for segment in segments {
+            disk.push(segment);
+        }
+        disk
+    }
 
-        for (empty_idx, item_idx) in empty_iter.zip(item_iter).take_while(|(e_i, i_i)| e_i < i_i)  {
-            self.0.swap(empty_idx, item_idx);
+    pub fn segments(&self) -> &[Segment] {
+        &self.0
+    }
+
+    /// Total number of blocks, free or not.
+    pub fn len(&self) -> usize {
+        self.0.iter().map(Segment::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn push(&mut self, segment: Segment) {
+        if segment.is_empty() {
+            return;
+        }
+
+        match (self.0.last_mut(), segment) {
+            (Some(Segment::Free { len }), Segment::Free { len: more }) => *len += more,
+            (Some(Segment::File { id, len }), Segment::File { id: next, len: more }) if *id == next => *len += more,
+            _ => self.0.push(segment),
         }
     }
 
-    pub fn filewise_defrag(&mut self) {
-        let mut item_iter = self.0.iter().enumerate().rev().filter(|(_, i)| matches!(i, DiskItem::Item { size: _, id: _ })).map(|(idx, _)| idx).collect::<Vec<_>>().into_iter().peekable();
-        let mut item_idx = item_iter.next().unwrap();
+    /// Files with their starting block, in disk order.
+    fn file_extents(&self) -> Vec<FileExtent> {
+        self.0.iter()
+            .scan(0, |start, segment| {
+                let extent_start = *start;
+                *start += segment.len();
+                Some((extent_start, *segment))
+            })
+            .filter_map(|(start, segment)| match segment {
+                Segment::File { id, len } => Some(FileExtent { id, start, len }),
+                Segment::Free { .. } => None,
+            })
+            .collect()
+    }
 
-        while item_idx > 0 {
-            let (item_size, item_id) = match self.0[item_idx] {
-                DiskItem::Empty { size: _ } => panic!("should never get an empty item"),
-                DiskItem::Item { size, id } => (size, id)
-            };
+    /// Free spans with their starting block, in disk order.
+    fn free_spans(&self) -> Vec<(usize, usize)> {
+        let mut position = 0;
+        let mut spans = Vec::new();
+        for segment in &self.0 {
+            if let Segment::Free { len } = segment {
+                spans.push((position, *len));
+            }
+            position += segment.len();
+        }
+        spans
+    }
 
-            let empty_idx = self.0.iter().enumerate().filter(|(_, i)| matches!(i, DiskItem::Empty { size: _ })).map(|(idx, item)| (idx, match item {
-                DiskItem::Empty { size } => *size,
-                DiskItem::Item { size: _, id: _ } => panic!("empty idx should never have an item"),
-            }))
-            .find(|(idx, size)| *size >= item_size && *idx < item_idx)
-            .map(|(idx, _)| idx);
+    /// The moves `compaction` makes, in order.
+    pub fn moves(&self, compaction: Compaction) -> Vec<Move> {
+        match compaction {
+            Compaction::Blocks => self.block_moves(),
+            Compaction::Files => self.file_moves(),
+        }
+    }
 
-            let mut empty_offset = 0;
+    /// Fills free space left to right with blocks taken from the end of the
+    /// disk. Blocks moving together out of the same file into the same span
+    /// are a single move.
+    fn block_moves(&self) -> Vec<Move> {
+        // every file along with the free space that follows it
+        let mut files = VecDeque::<(FileExtent, usize)>::new();
+        let mut leading_free = 0;
+        for extent in self.file_extents() {
+            match files.back_mut() {
+                Some((last, free)) => *free = extent.start - (last.start + last.len),
+                None => leading_free = extent.start,
+            }
+            files.push_back((extent, 0));
+        }
 
-            loop {
-                if let Some(empty_idx) = empty_idx {
-                    self.0.swap(empty_idx + empty_offset, item_idx);
-                    empty_offset += 1;
+        let mut moves = Vec::new();
+        let fill = |moves: &mut Vec<Move>, files: &mut VecDeque<(FileExtent, usize)>, mut to: usize, mut free: usize| {
+            while free > 0 {
+                let Some((back, _)) = files.back_mut() else { break };
+                let len = free.min(back.len);
+                back.len -= len;
+                moves.push(Move { id: back.id, from: back.start + back.len, to, len });
+                to += len;
+                free -= len;
+                if back.len == 0 {
+                    files.pop_back();
                 }
+            }
+        };
 
-                // println!("{self} {:?} {item_idx}", empty_idx.map(|i| i + empty_offset));
-                
-                if let Some(next_idx) = item_iter.next(){
-                    item_idx = next_idx
-                } else {
-                    break;
-                }
+        fill(&mut moves, &mut files, 0, leading_free);
+        while let Some((front, free)) = files.pop_front() {
+            fill(&mut moves, &mut files, front.start + front.len, free);
+        }
+
+        moves
+    }
+
+    /// Free spans are kept in one min-heap of start positions per span
+    /// length, so finding the leftmost fitting span only has to peek at one
+    /// heap per length that is large enough.
+    fn file_moves(&self) -> Vec<Move> {
+        let spans = self.free_spans();
+        let max_span = spans.iter().map(|(_, len)| *len).max().unwrap_or(0);
+        let mut free_by_len = vec![BinaryHeap::new(); max_span + 1];
+        for (start, len) in spans {
+            free_by_len[len].push(Reverse(start));
+        }
 
-                let next_item_id = match self.0[item_idx] {
-                    DiskItem::Empty { size: _ } => panic!("should never get an empty item"),
-                    DiskItem::Item { size: _, id } => id
-                };
+        let mut moves = Vec::new();
+        for file in self.file_extents().into_iter().rev() {
+            let best = (file.len..=max_span)
+                .filter_map(|len| free_by_len[len].peek().map(|Reverse(start)| (*start, len)))
+                .filter(|(start, _)| *start < file.start)
+                .min();
 
-                if next_item_id != item_id {
-                    break;
+            if let Some((start, len)) = best {
+                free_by_len[len].pop();
+                moves.push(Move { id: file.id, from: file.start, to: start, len: file.len });
+
+                let rest = len - file.len;
+                if rest > 0 {
+                    free_by_len[rest].push(Reverse(start + file.len));
                 }
             }
         }
+
+        moves
+    }
+
+    /// The disk after making every move in turn. Each move has to take
+    /// blocks of a single file and put them on free blocks.
+    pub fn with_moves(&self, moves: impl IntoIterator<Item = Move>) -> Disk {
+        let mut extents = self.file_extents()
+            .into_iter()
+            .map(|FileExtent { id, start, len }| (start, (id, len)))
+            .collect::<BTreeMap<_, _>>();
+
+        for Move { id, from, to, len } in moves {
+            let (&start, &(extent_id, extent_len)) = extents.range(..=from).next_back()
+                .expect("moves should start on a file");
+            debug_assert!(extent_id == id && from + len <= start + extent_len, "{id} doesn't have blocks {from}..{}", from + len);
+
+            extents.remove(&start);
+            if from > start {
+                extents.insert(start, (id, from - start));
+            }
+            if from + len < start + extent_len {
+                extents.insert(from + len, (id, start + extent_len - from - len));
+            }
+            extents.insert(to, (id, len));
+        }
+
+        let mut disk = Disk::default();
+        let mut position = 0;
+        for (start, (id, len)) in extents {
+            disk.push(Segment::Free { len: start - position });
+            disk.push(Segment::File { id, len });
+            position = start + len;
+        }
+        disk.push(Segment::Free { len: self.len() - position });
+
+        disk
+    }
+
+    pub fn compact(&mut self, compaction: Compaction) {
+        *self = self.with_moves(self.moves(compaction));
+    }
+
+    /// Moves single blocks from the end of the disk into the leftmost free
+    /// block until there are no gaps left, splitting files as needed.
+    pub fn compact_blocks(&mut self) {
+        self.compact(Compaction::Blocks);
+    }
+
+    /// Moves whole files, highest id first, into the leftmost free span
+    /// that fits them, if there is one before the file.
+    pub fn compact_files(&mut self) {
+        self.compact(Compaction::Files);
+    }
+
+    /// The disk after each move `compaction` makes, the last one being the
+    /// compacted disk.
+    pub fn steps(&self, compaction: Compaction) -> impl Iterator<Item = Disk> + '_ {
+        self.moves(compaction)
+            .into_iter()
+            .scan(self.clone(), |disk, step| {
+                *disk = disk.with_moves([step]);
+                Some(disk.clone())
+            })
     }
 
     pub fn checksum(&self) -> usize {
-        self.0.iter().enumerate().map(|(idx, item)| {
-            match item {
-                DiskItem::Empty { size: _ } => 0,
-                DiskItem::Item{ size: _, id } => idx * id,
+        self.file_extents()
+            .into_iter()
+            .map(|FileExtent { id, start, len }| id * (start..start + len).sum::<usize>())
+            .sum()
+    }
+
+    pub fn render(&self, style: RenderStyle) -> String {
+        let mut rendered = String::new();
+        for segment in &self.0 {
+            let block = match (segment, style) {
+                (Segment::Free { .. }, _) => ".".to_string(),
+                (Segment::File { id, .. }, RenderStyle::Palette) => (PALETTE[id % PALETTE.len()] as char).to_string(),
+                (Segment::File { id, .. }, RenderStyle::Bracketed) => format!("[{id}]"),
+            };
+            rendered.push_str(&block.repeat(segment.len()));
+        }
+        rendered
+    }
+
+    /// Back to the dense disk map the puzzle uses, which [`parser::parse`]
+    /// reads back as the same disk.
+    ///
+    /// Files missing from the disk are written as empty files, so ids still
+    /// line up, and free runs too long for one digit are spread over the
+    /// free digits around those empty files. Empty files at the very end
+    /// leave no trace on the disk, so they aren't written back.
+    pub fn encode(&self) -> Result<String, EncodeError> {
+        let digit = |len: usize| match len {
+            0..=9 => Ok(char::from(b'0' + len as u8)),
+            _ => Err(EncodeError::TooLong { len }),
+        };
+        // as much of the free run as one digit holds
+        let take = |free: &mut usize| {
+            let len = (*free).min(9);
+            *free -= len;
+            char::from(b'0' + len as u8)
+        };
+
+        let mut encoded = String::new();
+        let mut next_id = 0;
+        // free blocks since the last file
+        let mut free = 0;
+
+        for segment in &self.0 {
+            match *segment {
+                Segment::Free { len } => free += len,
+                Segment::File { id, len } => {
+                    // nothing can come before file 0, not even free space
+                    if id < next_id || (id == 0 && free > 0) {
+                        return Err(EncodeError::OutOfOrder { id, expected: next_id.max(1) });
+                    }
+
+                    // a free digit after the last file and after each empty one
+                    let slots = id - next_id + usize::from(next_id > 0);
+                    if free > 9 * slots {
+                        return Err(EncodeError::TooLong { len: free });
+                    }
+
+                    if next_id > 0 {
+                        encoded.push(take(&mut free));
+                    }
+                    for _ in next_id..id {
+                        encoded.push('0');
+                        encoded.push(take(&mut free));
+                    }
+                    encoded.push(digit(len)?);
+                    next_id = id + 1;
+                }
             }
-        })
-        .sum()
+        }
+
+        // past the last file there are as many empty files as it takes
+        if free > 0 && next_id > 0 {
+            encoded.push(take(&mut free));
+        }
+        while free > 0 {
+            encoded.push('0');
+            encoded.push(take(&mut free));
+        }
+
+        Ok(encoded)
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum DiskItem {
-    Empty {
-        size: usize
-    },
-    Item {
-        size: usize,
-        id: usize,
-    }
-}
-impl Display for DiskItem {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", match self {
-            DiskItem::Empty { size: _ } => ".".to_string(),
-            DiskItem::Item { size: _, id } => id.to_string()
-        })
-    }
+struct FileExtent {
+    id: usize,
+    start: usize,
+    len: usize,
 }
+
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+    use test_case::test_case;
 
-}
+    use super::{parser::parse, Compaction, Disk, EncodeError, RenderStyle, Segment::*};
 
-pub mod parser {
-    use super::{Disk, DiskItem};
+    const SAMPLE: &str = "2333133121414131402";
 
-    enum ParserState {
-        Item(usize),
-        Empty(usize),
+    #[test_case("12345", "022111222......")]
+    #[test_case(SAMPLE, "0099811188827773336446555566..............")]
+    fn test_compact_blocks(input: &str, expected: &str) {
+        let mut disk = parse(input);
+        disk.compact_blocks();
+        assert_eq!(disk.to_string(), expected);
     }
 
+    #[test_case("12345", "0..111....22222")]
+    #[test_case(SAMPLE, "00992111777.44.333....5555.6666.....8888..")]
+    fn test_compact_files(input: &str, expected: &str) {
+        let mut disk = parse(input);
+        disk.compact_files();
+        assert_eq!(disk.to_string(), expected);
+    }
+
+    #[test]
+    fn test_compact_files_prefers_leftmost_span() {
+        // the 3-wide span at block 1 wins over the exact fit at block 5
+        let mut disk = parse("13122");
+        assert_eq!(disk.to_string(), "0...1..22");
+        disk.compact_files();
+        assert_eq!(disk.to_string(), "0221.....");
+    }
+
+    #[test]
+    fn test_block_steps() {
+        let steps = parse("12345").steps(Compaction::Blocks).map(|disk| disk.to_string()).collect::<Vec<_>>();
+        assert_eq!(steps, ["022111....222..", "022111222......"]);
+    }
+
+    #[test]
+    fn test_file_steps() {
+        let steps = parse(SAMPLE).steps(Compaction::Files).map(|disk| disk.to_string()).collect::<Vec<_>>();
+        assert_eq!(steps, [
+            "0099.111...2...333.44.5555.6666.777.8888..",
+            "0099.1117772...333.44.5555.6666.....8888..",
+            "0099.111777244.333....5555.6666.....8888..",
+            "00992111777.44.333....5555.6666.....8888..",
+        ]);
+    }
+
+    #[test_case(Compaction::Blocks)]
+    #[test_case(Compaction::Files)]
+    fn test_last_step_is_compacted(compaction: Compaction) {
+        let disk = parse(SAMPLE);
+        let mut compacted = disk.clone();
+        compacted.compact(compaction);
+
+        assert_eq!(disk.steps(compaction).last(), Some(compacted));
+    }
+
+    #[test_case("12345")]
+    #[test_case(SAMPLE)]
+    #[test_case("90909")]
+    #[test_case("1909"; "merged free runs")]
+    #[test_case("0909"; "only free space")]
+    #[test_case("190909002"; "free run over several empty files")]
+    fn test_encode_round_trip(input: &str) {
+        assert_eq!(parse(input).encode(), Ok(input.to_string()));
+    }
+
+    #[test]
+    fn test_encode_drops_trailing_empty_files() {
+        assert_eq!(parse("120").encode(), Ok("12".to_string()));
+        assert_eq!(parse("12"), parse("120"));
+    }
+
+    proptest! {
+        #[test]
+        fn test_encode_reads_back(input in "[0-9]{0,40}") {
+            let disk = parse(&input);
+            prop_assert_eq!(disk.encode().map(|encoded| parse(&encoded)), Ok(disk));
+        }
+    }
+
+    #[test]
+    fn test_encode_missing_and_empty_files() {
+        // file 1 is empty and disappears from the segments
+        let disk = parse("10013");
+        assert_eq!(disk.segments(), [File { id: 0, len: 1 }, Free { len: 1 }, File { id: 2, len: 3 }]);
+        assert_eq!(disk.encode(), Ok("11003".to_string()));
+        assert_eq!(parse("11003"), disk);
+
+        let disk = Disk::from_segments([Free { len: 2 }, File { id: 1, len: 1 }]);
+        assert_eq!(disk.encode(), Ok("021".to_string()));
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let mut disk = parse(SAMPLE);
+        disk.compact_files();
+        assert_eq!(disk.encode(), Err(EncodeError::OutOfOrder { id: 2, expected: 10 }));
+
+        let mut disk = parse(SAMPLE);
+        disk.compact_blocks();
+        assert_eq!(disk.encode(), Err(EncodeError::OutOfOrder { id: 8, expected: 10 }));
+
+        let disk = Disk::from_segments([File { id: 0, len: 6 }, File { id: 0, len: 6 }]);
+        assert_eq!(disk.encode(), Err(EncodeError::TooLong { len: 12 }));
+
+        // only one free digit fits between neighbouring files
+        let disk = Disk::from_segments([File { id: 0, len: 1 }, Free { len: 10 }, File { id: 1, len: 1 }]);
+        assert_eq!(disk.encode(), Err(EncodeError::TooLong { len: 10 }));
+
+        let disk = Disk::from_segments([Free { len: 1 }, File { id: 0, len: 1 }]);
+        assert_eq!(disk.encode(), Err(EncodeError::OutOfOrder { id: 0, expected: 1 }));
+    }
+
+    #[test]
+    fn test_render_multi_digit_ids() {
+        let disk = Disk::from_segments([File { id: 9, len: 1 }, File { id: 10, len: 2 }, Free { len: 1 }, File { id: 62, len: 1 }]);
+
+        assert_eq!(disk.render(RenderStyle::Palette), "9aa.0");
+        assert_eq!(disk.render(RenderStyle::Bracketed), "[9][10][10].[62]");
+        assert_eq!(disk.to_string(), disk.render(RenderStyle::Bracketed));
+
+        let disk = Disk::from_segments([File { id: 9, len: 1 }, File { id: 61, len: 1 }]);
+        assert_eq!(disk.to_string(), "9Z");
+    }
+
+    #[test_case(SAMPLE, 1928, 2858)]
+    fn test_checksum(input: &str, blocks: usize, files: usize) {
+        let mut disk = parse(input);
+        disk.compact_blocks();
+        assert_eq!(disk.checksum(), blocks);
+
+        let mut disk = parse(input);
+        disk.compact_files();
+        assert_eq!(disk.checksum(), files);
+    }
+}
+
+pub mod parser {
+    use super::{Disk, Segment};
+
+    /// Reads the dense disk map, digits alternate between file and free
+    /// space lengths starting with a file.
     pub fn parse(input: &str) -> Disk {
-        Disk(input.chars()
-            .flat_map(|c| c.to_digit(10))
-            .map(|item| item as usize)
-            .fold(
-            (ParserState::Item(0), Vec::new()),
-            |(state, mut acc), next| {
-                for i in (1..=next).rev() {
-                    acc.push(match state {
-                        ParserState::Item(id) => DiskItem::Item { size: next, id: id },
-                        ParserState::Empty(_) => DiskItem::Empty { size: i }
-                    });
-                };
-
-                (match state {
-                    ParserState::Item(id) => ParserState::Empty(id),
-                    ParserState::Empty(id) => ParserState::Item(id + 1),
-                }, acc)
-            })
-            .1)
+        Disk::from_segments(
+            input.chars()
+                .filter_map(|c| c.to_digit(10))
+                .map(|len| len as usize)
+                .enumerate()
+                .map(|(idx, len)| if idx % 2 == 0 {
+                    Segment::File { id: idx / 2, len }
+                } else {
+                    Segment::Free { len }
+                })
+        )
     }
 
     #[cfg(test)]
     mod test {
         use test_case::test_case;
 
-        use crate::day9::{parser::parse, DiskItem};
+        use crate::day9::{parser::parse, Disk, Segment::*};
 
-        use crate::day9::{Disk, DiskItem::*};
-
-        #[test_case("3", Disk(vec![DiskItem::Item { id: 0, size: 3 }, DiskItem::Item { id: 0, size: 3}, DiskItem::Item { id: 0, size: 3}]))]
-        #[test_case("22", Disk(vec![Item { id: 0, size: 2 }, Item { id: 0, size: 2 }, Empty { size: 2 }, Empty { size: 1 }]))]
-        #[test_case("222", Disk(vec![Item { id: 0, size: 2 }, Item { id: 0, size: 2 },Empty { size: 2}, Empty { size: 1} ,Item { id: 1, size: 2 }, Item { id: 1, size: 2 },]))]
-        #[test_case("22202", Disk(vec![Item{ id: 0, size: 2 },Item{ id: 0, size: 2 },Empty{ size: 2}, Empty{ size: 1},Item{ id: 1, size: 2 },Item{ id: 1, size: 2 },Item{ id: 2, size: 2 },Item{ id: 2, size: 2 },]))]
-        #[test_case("12345", Disk(vec![Item{ id: 0, size: 1 }, Empty{ size: 2}, Empty{ size: 1}, Item{ id: 1, size: 3 }, Item{ id: 1, size: 3 }, Item{ id: 1, size: 3 }, Empty{ size: 4}, Empty{ size: 3}, Empty{ size: 2}, Empty{ size: 1}, Item{ id: 2, size: 5 },Item{ id: 2, size: 5 },Item{ id: 2, size: 5 },Item{ id: 2, size: 5 },Item{ id: 2, size: 5 },]))]
-        fn test_parse_compacted(input: &str, expected: Disk) {
+        #[test_case("3", Disk(vec![File { id: 0, len: 3 }]))]
+        #[test_case("22", Disk(vec![File { id: 0, len: 2 }, Free { len: 2 }]))]
+        #[test_case("222", Disk(vec![File { id: 0, len: 2 }, Free { len: 2 }, File { id: 1, len: 2 }]))]
+        #[test_case("22202", Disk(vec![File { id: 0, len: 2 }, Free { len: 2 }, File { id: 1, len: 2 }, File { id: 2, len: 2 }]))]
+        #[test_case("12345", Disk(vec![File { id: 0, len: 1 }, Free { len: 2 }, File { id: 1, len: 3 }, Free { len: 4 }, File { id: 2, len: 5 }]))]
+        #[test_case("12345\n", Disk(vec![File { id: 0, len: 1 }, Free { len: 2 }, File { id: 1, len: 3 }, Free { len: 4 }, File { id: 2, len: 5 }]))]
+        fn test_parse(input: &str, expected: Disk) {
             let actual = parse(input);
             assert_eq!(actual, expected);
         }
 
-        #[test_case("12345")]
-        #[ignore = "for debugging purposes"]
-        fn test_display(input: &str) {
-            println!("{}", parse(input));
-            assert!(false);
-        }
-
-        #[ignore = "for debugging purposes"]
-        #[test_case("12345", false)]
-        #[test_case("2333133121414131402", false)]
-        fn test_defrag(input: &str, expected: bool) {
-            let mut disk = parse(input);
-            disk.defrag();
-            println!("{disk}");
-            assert!(expected);
-        }
-
-        // #[ignore = "for debugging purposes"]
-        #[test_case("12345", false)]
-        #[test_case("2333133121414131402", false)]
-        fn test_file_defrag(input: &str, expected: bool) {
-            let mut disk = parse(input);
-            println!("{disk}");
-            disk.filewise_defrag();
-            println!("{disk}");
-            assert!(expected);
+        #[test]
+        fn test_display() {
+            assert_eq!(parse("2333133121414131402").to_string(), "00...111...2...333.44.5555.6666.777.888899");
         }
     }
-}
\ No newline at end of file
+}
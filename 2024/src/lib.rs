@@ -8,4 +8,5 @@ pub mod day7;
 pub mod day8;
 pub mod day9;
 pub mod day10;
-pub mod day11;
\ No newline at end of file
+pub mod day11;
+pub mod day12;
\ No newline at end of file
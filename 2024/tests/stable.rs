@@ -0,0 +1,78 @@
+//! Everything in the workspace is meant to build on stable Rust.
+
+use std::{fs, path::{Path, PathBuf}, process::Command};
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("aoc-2024 lives inside the workspace")
+        .to_path_buf()
+}
+
+fn rust_sources(dir: &Path, sources: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        if name == "target" || name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            rust_sources(&path, sources);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            sources.push(path);
+        }
+    }
+}
+
+#[test]
+fn no_nightly_features() {
+    let mut sources = Vec::new();
+    rust_sources(&workspace_root(), &mut sources);
+
+    let nightly = sources
+        .into_iter()
+        .filter(|path| {
+            fs::read_to_string(path)
+                .unwrap()
+                .lines()
+                .any(|line| line.trim_start().starts_with("#![feature"))
+        })
+        .collect::<Vec<_>>();
+
+    assert!(nightly.is_empty(), "nightly features used in {nightly:?}");
+}
+
+/// `cargo` from the stable toolchain, `None` when rustup or the toolchain
+/// isn't installed.
+fn stable_cargo() -> Option<Command> {
+    let probe = Command::new("rustup")
+        .args(["run", "stable", "cargo", "--version"])
+        .output()
+        .ok()?;
+
+    probe.status.success().then(|| {
+        let mut cargo = Command::new("rustup");
+        cargo.args(["run", "stable", "cargo"]);
+        cargo
+    })
+}
+
+#[test]
+fn workspace_checks_on_stable() {
+    let Some(mut cargo) = stable_cargo() else {
+        eprintln!("skipping, no stable toolchain through rustup");
+        return;
+    };
+
+    let root = workspace_root();
+    let status = cargo
+        .args(["check", "--workspace", "--all-targets"])
+        .env("CARGO_TARGET_DIR", root.join("target").join("stable-check"))
+        .env_remove("RUSTC_BOOTSTRAP")
+        .current_dir(&root)
+        .status()
+        .unwrap();
+
+    assert!(status.success(), "the workspace doesn't build on stable");
+}
@@ -157,20 +157,15 @@ mod test {
     }
 }
 mod parser {
-    use nom::{
-        bytes::complete::tag, character::complete::u64, combinator::map,
-        multi::separated_list1, sequence::separated_pair, IResult,
-    };
+    use aoc_parse::{range, separated};
+    use nom::IResult;
 
     pub fn parse_ranges(input: &str) -> IResult<&str, Vec<std::ops::RangeInclusive<u64>>> {
-        separated_list1(tag(","), parse_range)(input)
+        separated(',', parse_range)(input)
     }
 
     pub fn parse_range(input: &str) -> IResult<&str, std::ops::RangeInclusive<u64>> {
-        map(
-            separated_pair(u64, tag("-"), u64),
-            |(start, end)| start..=end,
-        )(input)
+        range(input)
     }
     #[cfg(test)]
     mod test {
@@ -0,0 +1,331 @@
+//! Points and headings on a grid. `x` grows to the east and `y` to the south,
+//! the way puzzle maps are read, so a row index is a `y` and a column an `x`.
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// The integers a [`Vec2`] can be made of.
+pub trait Scalar:
+    Copy
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + TryInto<usize>
+    + TryFrom<usize>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// How far apart the two are, whichever is bigger.
+    fn distance(self, other: Self) -> Self;
+}
+
+/// The [`Scalar`]s that can be negative.
+pub trait SignedScalar: Scalar + Neg<Output = Self> {
+    fn signum(self) -> Self;
+}
+
+macro_rules! unsigned_scalar {
+    ($($t:ty),*) => {$(
+        impl Scalar for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn distance(self, other: Self) -> Self {
+                self.abs_diff(other)
+            }
+        }
+    )*};
+}
+
+macro_rules! signed_scalar {
+    ($($t:ty),*) => {$(
+        impl Scalar for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn distance(self, other: Self) -> Self {
+                (self - other).abs()
+            }
+        }
+
+        impl SignedScalar for $t {
+            fn signum(self) -> Self {
+                <$t>::signum(self)
+            }
+        }
+    )*};
+}
+
+unsigned_scalar!(u8, u16, u32, u64, u128, usize);
+signed_scalar!(i8, i16, i32, i64, i128, isize);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Vec2 { x, y }
+    }
+}
+
+impl<T: Scalar> Vec2<T> {
+    pub const ZERO: Self = Vec2::new(T::ZERO, T::ZERO);
+
+    /// Steps between the two moving only along the axes.
+    pub fn manhattan(self, other: Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    /// Steps between the two when diagonal steps are allowed too.
+    pub fn chebyshev(self, other: Self) -> T {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+
+    /// The point at `(row, col)` of a grid, if it fits in a `T`.
+    pub fn from_grid((row, col): (usize, usize)) -> Option<Self> {
+        Some(Vec2::new(col.try_into().ok()?, row.try_into().ok()?))
+    }
+
+    /// `(row, col)` of the point on a `width` by `height` grid, `None` when
+    /// it's off the grid.
+    pub fn to_grid(self, width: usize, height: usize) -> Option<(usize, usize)> {
+        let col: usize = self.x.try_into().ok()?;
+        let row: usize = self.y.try_into().ok()?;
+
+        (col < width && row < height).then_some((row, col))
+    }
+
+    /// Where the point is in a `width` by `height` grid stored row by row.
+    pub fn to_index(self, width: usize, height: usize) -> Option<usize> {
+        self.to_grid(width, height)
+            .map(|(row, col)| row * width + col)
+    }
+}
+
+impl<T: SignedScalar> Vec2<T> {
+    /// A quarter turn clockwise as seen on the map, so north becomes east.
+    pub fn rotate_right(self) -> Self {
+        Vec2::new(-self.y, self.x)
+    }
+
+    /// A quarter turn anticlockwise as seen on the map, so north becomes west.
+    pub fn rotate_left(self) -> Self {
+        Vec2::new(self.y, -self.x)
+    }
+
+    /// Each coordinate as -1, 0 or 1, the single step that goes the same way.
+    pub fn signum(self) -> Self {
+        Vec2::new(self.x.signum(), self.y.signum())
+    }
+
+    /// One step towards `heading`.
+    pub fn step(self, heading: Heading) -> Self {
+        self + heading.unit()
+    }
+}
+
+impl<T: Add<Output = T>> Add for Vec2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Vec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Vec2<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Vec2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Vec2<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Vec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Vec2<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Vec2<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+/// One of the four ways to go on a grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Heading {
+    #[default]
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Heading {
+    /// Clockwise from north.
+    pub const ALL: [Heading; 4] = [Heading::North, Heading::East, Heading::South, Heading::West];
+
+    pub fn turn_right(self) -> Self {
+        Heading::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Heading::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Heading::ALL[(self.index() + 2) % 4]
+    }
+
+    /// Where it is in [`Heading::ALL`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// A single step this way.
+    pub fn unit<T: SignedScalar>(self) -> Vec2<T> {
+        match self {
+            Heading::North => Vec2::new(T::ZERO, -T::ONE),
+            Heading::East => Vec2::new(T::ONE, T::ZERO),
+            Heading::South => Vec2::new(T::ZERO, T::ONE),
+            Heading::West => Vec2::new(-T::ONE, T::ZERO),
+        }
+    }
+
+    /// `^`, `>`, `v` or `<`.
+    pub fn arrow(self) -> char {
+        ['^', '>', 'v', '<'][self.index()]
+    }
+
+    pub fn from_arrow(arrow: char) -> Option<Self> {
+        Heading::ALL
+            .into_iter()
+            .find(|heading| heading.arrow() == arrow)
+    }
+
+    /// `U`, `R`, `D` or `L` for up, right, down or left.
+    pub fn from_letter(letter: char) -> Option<Self> {
+        let idx = ['U', 'R', 'D', 'L'].iter().position(|&c| c == letter)?;
+        Some(Heading::ALL[idx])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+    use test_case::test_case;
+
+    #[test]
+    fn test_arithmetic() {
+        let (a, b) = (Vec2::new(3, -2), Vec2::new(-1, 5));
+
+        assert_eq!(a + b, Vec2::new(2, 3));
+        assert_eq!(a - b, Vec2::new(4, -7));
+        assert_eq!(-a, Vec2::new(-3, 2));
+        assert_eq!(a * 3, Vec2::new(9, -6));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+    }
+
+    #[test_case(Vec2::new(0, 0), Vec2::new(3, -4), 7, 4)]
+    #[test_case(Vec2::new(-2, 5), Vec2::new(-2, 5), 0, 0)]
+    #[test_case(Vec2::new(1, 1), Vec2::new(2, 2), 2, 1)]
+    fn test_distances(a: Vec2<i64>, b: Vec2<i64>, manhattan: i64, chebyshev: i64) {
+        assert_eq!(a.manhattan(b), manhattan);
+        assert_eq!(a.chebyshev(b), chebyshev);
+    }
+
+    #[test]
+    fn test_unsigned_distances() {
+        assert_eq!(Vec2::<usize>::new(5, 1).manhattan(Vec2::new(2, 4)), 6);
+        assert_eq!(Vec2::<u8>::new(0, 9).chebyshev(Vec2::new(7, 0)), 9);
+    }
+
+    #[test_case(Heading::North, Heading::East, Heading::West)]
+    #[test_case(Heading::East, Heading::South, Heading::North)]
+    #[test_case(Heading::South, Heading::West, Heading::East)]
+    #[test_case(Heading::West, Heading::North, Heading::South)]
+    fn test_turns(heading: Heading, right: Heading, left: Heading) {
+        assert_eq!(heading.turn_right(), right);
+        assert_eq!(heading.turn_left(), left);
+        assert_eq!(heading.unit::<i32>().rotate_right(), right.unit());
+        assert_eq!(heading.unit::<i32>().rotate_left(), left.unit());
+        assert_eq!(heading.reverse().unit::<i32>(), -heading.unit::<i32>());
+    }
+
+    #[test]
+    fn test_glyphs() {
+        assert_eq!(Heading::from_arrow('v'), Some(Heading::South));
+        assert_eq!(Heading::from_arrow('x'), None);
+        assert_eq!(Heading::from_letter('L'), Some(Heading::West));
+        assert_eq!(Heading::ALL.map(Heading::arrow), ['^', '>', 'v', '<']);
+    }
+
+    #[test_case(Vec2::new(2, 1), Some((1, 2)))]
+    #[test_case(Vec2::new(-1, 1), None)]
+    #[test_case(Vec2::new(1, -1), None)]
+    #[test_case(Vec2::new(3, 0), None)]
+    #[test_case(Vec2::new(0, 2), None)]
+    fn test_to_grid(point: Vec2<i64>, expected: Option<(usize, usize)>) {
+        assert_eq!(point.to_grid(3, 2), expected);
+        assert_eq!(
+            point.to_index(3, 2),
+            expected.map(|(row, col)| row * 3 + col)
+        );
+    }
+
+    #[test]
+    fn test_from_grid() {
+        assert_eq!(Vec2::<i64>::from_grid((4, 7)), Some(Vec2::new(7, 4)));
+        assert_eq!(Vec2::<i8>::from_grid((0, 200)), None);
+        assert_eq!(Vec2::new(7i64, 4).step(Heading::North), Vec2::new(7, 3));
+    }
+
+    proptest! {
+        #[test]
+        fn test_rotation(x in -1000i64..1000, y in -1000i64..1000) {
+            let point = Vec2::new(x, y);
+            let turned = point.rotate_right();
+
+            prop_assert_eq!(turned.rotate_left(), point);
+            prop_assert_eq!(turned.rotate_right().rotate_right().rotate_right(), point);
+            prop_assert_eq!(turned.manhattan(Vec2::ZERO), point.manhattan(Vec2::ZERO));
+        }
+
+        #[test]
+        fn test_signum_is_a_chebyshev_step(x in -50i64..50, y in -50i64..50) {
+            let point = Vec2::new(x, y);
+            let closer = point - point.signum();
+
+            prop_assert!(point.signum().chebyshev(Vec2::ZERO) <= 1);
+            prop_assert_eq!(closer.chebyshev(Vec2::ZERO), (point.chebyshev(Vec2::ZERO) - 1).max(0));
+        }
+    }
+}
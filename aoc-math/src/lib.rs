@@ -0,0 +1,361 @@
+//! Number theory that keeps turning up in puzzles: gcd and lcm, modular
+//! arithmetic and the Chinese Remainder Theorem, for every primitive integer
+//! up to `u128` and `i128`.
+//!
+//! A modulus has to be positive, a zero one panics like `%` does.
+
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// The integers the functions here work on.
+pub trait Integer:
+    Copy
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// The absolute value, which overflows for the smallest signed value.
+    fn magnitude(self) -> Self;
+
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+    /// `self % rhs`, which is 0 rather than an overflow for the smallest
+    /// signed value and -1.
+    fn wrapping_rem(self, rhs: Self) -> Self;
+
+    /// The remainder in `0..modulus`, even for negative `self`.
+    fn modulo(self, modulus: Self) -> Self;
+}
+
+/// The [`Integer`]s that can be negative.
+pub trait SignedInteger: Integer {}
+
+macro_rules! unsigned_integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn magnitude(self) -> Self {
+                self
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn wrapping_rem(self, rhs: Self) -> Self {
+                <$t>::wrapping_rem(self, rhs)
+            }
+
+            fn modulo(self, modulus: Self) -> Self {
+                self % modulus
+            }
+        }
+    )*};
+}
+
+macro_rules! signed_integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn magnitude(self) -> Self {
+                self.abs()
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn wrapping_rem(self, rhs: Self) -> Self {
+                <$t>::wrapping_rem(self, rhs)
+            }
+
+            fn modulo(self, modulus: Self) -> Self {
+                self.rem_euclid(modulus)
+            }
+        }
+
+        impl SignedInteger for $t {}
+    )*};
+}
+
+unsigned_integer!(u8, u16, u32, u64, u128, usize);
+signed_integer!(i8, i16, i32, i64, i128, isize);
+
+/// The greatest common divisor, never negative. `gcd(0, 0)` is 0.
+///
+/// The only gcd that doesn't fit is that of the smallest signed value with
+/// itself or 0, which overflows like `abs` does.
+pub fn gcd<T: Integer>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a.wrapping_rem(b));
+    }
+
+    a.magnitude()
+}
+
+/// The least common multiple, never negative. It's 0 if either is 0 and
+/// overflows like `*` does.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+
+    (a / gcd(a, b) * b).magnitude()
+}
+
+/// The gcd of all the `values`, 0 if there are none.
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::ZERO, gcd)
+}
+
+/// The lcm of all the `values`, 1 if there are none.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::ONE, lcm)
+}
+
+/// `(g, x, y)` where `g` is `gcd(a, b)` and `a * x + b * y == g`.
+pub fn extended_gcd<T: SignedInteger>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < T::ZERO {
+        (T::ZERO - old_r, T::ZERO - old_x, T::ZERO - old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `(a + b) % modulus` without overflowing, for `a` and `b` already in
+/// `0..modulus`.
+fn add_mod<T: Integer>(a: T, b: T, modulus: T) -> T {
+    if a >= modulus - b {
+        a - (modulus - b)
+    } else {
+        a + b
+    }
+}
+
+/// `(a * b) % modulus` without overflowing, whatever the size of the product.
+pub fn mul_mod<T: Integer>(a: T, b: T, modulus: T) -> T {
+    let (mut a, mut b) = (a.modulo(modulus), b.modulo(modulus));
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+
+    // double and add, which only ever needs sums below `2 * modulus`
+    let two = T::ONE + T::ONE;
+    let mut product = T::ZERO;
+    while b != T::ZERO {
+        if b % two == T::ONE {
+            product = add_mod(product, a, modulus);
+        }
+        a = add_mod(a, a, modulus);
+        b = b / two;
+    }
+
+    product
+}
+
+/// `base` to the power of `exp`, modulo `modulus`. `exp` can't be negative.
+pub fn mod_pow<T: Integer>(base: T, mut exp: T, modulus: T) -> T {
+    debug_assert!(exp >= T::ZERO, "negative exponent");
+
+    let two = T::ONE + T::ONE;
+    let mut base = base.modulo(modulus);
+    let mut result = T::ONE % modulus;
+    while exp > T::ZERO {
+        if exp % two == T::ONE {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp = exp / two;
+    }
+
+    result
+}
+
+/// The `x` in `0..modulus` with `a * x` being 1 modulo `modulus`, if `a` and
+/// `modulus` are coprime.
+pub fn mod_inverse<T: SignedInteger>(a: T, modulus: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(a.modulo(modulus), modulus);
+
+    (g == T::ONE).then(|| x.modulo(modulus))
+}
+
+/// The `x` with `x % modulus == residue` for every pair in `congruences`,
+/// along with the lcm of the moduli that all the others are `x` plus a
+/// multiple of. `x` is in `0..lcm`.
+///
+/// The moduli needn't be coprime. `None` when the congruences contradict each
+/// other, a modulus isn't positive or the lcm doesn't fit in a `T`. With no
+/// congruences at all every number fits, giving `(0, 1)`.
+pub fn crt<T: SignedInteger>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    congruences
+        .into_iter()
+        .try_fold((T::ZERO, T::ONE), |(x, lcm), (residue, modulus)| {
+            if modulus <= T::ZERO {
+                return None;
+            }
+
+            // x + lcm * k = residue (mod modulus), solved for k
+            let g = gcd(lcm, modulus);
+            let diff = residue.modulo(modulus) - x.modulo(modulus);
+            if diff % g != T::ZERO {
+                return None;
+            }
+
+            let step = modulus / g;
+            let k = mul_mod(diff / g, mod_inverse(lcm / g, step)?, step);
+            let merged = lcm.checked_mul(step)?;
+
+            // k < step, so lcm * k + x stays below the merged lcm
+            Some((lcm * k + x, merged))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::*;
+    use test_case::test_case;
+
+    #[test_case(12, 18, 6, 36)]
+    #[test_case(-12, 18, 6, 36)]
+    #[test_case(7, 0, 7, 0)]
+    #[test_case(0, 0, 0, 0)]
+    #[test_case(17, 5, 1, 85)]
+    fn test_gcd_lcm(a: i64, b: i64, expected_gcd: i64, expected_lcm: i64) {
+        assert_eq!(gcd(a, b), expected_gcd);
+        assert_eq!(lcm(a, b), expected_lcm);
+    }
+
+    #[test]
+    fn test_gcd_extremes() {
+        assert_eq!(gcd(i64::MIN, -1), 1);
+        assert_eq!(gcd(-1, i64::MIN), 1);
+        assert_eq!(gcd(i64::MIN, i64::MAX), 1);
+        assert_eq!(gcd(i128::MIN, 6), 2);
+        assert_eq!(gcd(u128::MAX, u128::MAX - 1), 1);
+    }
+
+    #[test]
+    fn test_over_iterators() {
+        assert_eq!(gcd_all([24u64, 60, 36]), 12);
+        assert_eq!(lcm_all([2u64, 3, 4, 6]), 12);
+        assert_eq!(gcd_all::<u8>([]), 0);
+        assert_eq!(lcm_all::<u8>([]), 1);
+        // the 2022 day 11 sample divisors
+        assert_eq!(lcm_all([23u64, 19, 13, 17]), 96577);
+    }
+
+    #[test_case(3, 11, Some(4))]
+    #[test_case(-3, 11, Some(7))]
+    #[test_case(10, 17, Some(12))]
+    #[test_case(6, 9, None)]
+    #[test_case(5, 1, Some(0))]
+    fn test_mod_inverse(a: i64, modulus: i64, expected: Option<i64>) {
+        assert_eq!(mod_inverse(a, modulus), expected);
+    }
+
+    #[test_case(4, 13, 497, 445)]
+    #[test_case(2, 0, 7, 1)]
+    #[test_case(-2, 3, 7, 6)]
+    #[test_case(9, 5, 1, 0)]
+    fn test_mod_pow(base: i64, exp: i64, modulus: i64, expected: i64) {
+        assert_eq!(mod_pow(base, exp, modulus), expected);
+    }
+
+    #[test]
+    fn test_wide_integers() {
+        let big = u128::MAX - 158; // a prime
+        assert_eq!(mul_mod(big - 1, big - 1, big), 1);
+        assert_eq!(mod_pow(3, big - 1, big), 1);
+        assert_eq!(mul_mod(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+
+        let modulus = i128::MAX;
+        let inverse = mod_inverse(i128::MAX - 2, modulus).unwrap();
+        assert_eq!(mul_mod(inverse, i128::MAX - 2, modulus), 1);
+    }
+
+    #[test_case(&[(2, 3), (3, 5), (2, 7)], Some((23, 105)); "coprime")]
+    #[test_case(&[(1, 4), (3, 6)], Some((9, 12)); "shared factor")]
+    #[test_case(&[(1, 4), (2, 6)], None; "contradiction")]
+    #[test_case(&[(-1, 4), (13, 3)], Some((7, 12)); "unreduced residues")]
+    #[test_case(&[(1, 0)], None; "zero modulus")]
+    #[test_case(&[], Some((0, 1)); "nothing")]
+    fn test_crt(congruences: &[(i64, i64)], expected: Option<(i64, i64)>) {
+        assert_eq!(crt(congruences.iter().copied()), expected);
+    }
+
+    #[test]
+    fn test_crt_overflow() {
+        let primes = [1_000_000_007, 998_244_353, 1_000_000_009];
+        assert_eq!(crt(primes.map(|p: i64| (1, p))), None);
+
+        let wide = primes.map(i128::from);
+        assert_eq!(crt(wide.map(|p| (1, p))), Some((1, wide.iter().product())));
+    }
+
+    proptest! {
+        #[test]
+        fn test_gcd_divides(a in -10_000i64..10_000, b in -10_000i64..10_000) {
+            let g = gcd(a, b);
+            let (eg, x, y) = extended_gcd(a, b);
+
+            prop_assert_eq!(eg, g);
+            prop_assert_eq!(a * x + b * y, g);
+            prop_assert_eq!(g * lcm(a, b), (a * b).abs());
+            if g != 0 {
+                prop_assert!(a % g == 0 && b % g == 0);
+            }
+        }
+
+        #[test]
+        fn test_mul_mod_matches_wide(a: u64, b: u64, modulus in 1u64..) {
+            let wide = (a as u128 * b as u128 % modulus as u128) as u64;
+
+            prop_assert_eq!(mul_mod(a, b, modulus), wide);
+            prop_assert_eq!(mul_mod(a as u128, b as u128, modulus as u128), wide as u128);
+        }
+
+        #[test]
+        fn test_mod_pow_matches_repeated_mul(base in -100i64..100, exp in 0i64..50, modulus in 1i64..1000) {
+            let naive = (0..exp).fold(1 % modulus, |acc, _| (acc * base).rem_euclid(modulus));
+
+            prop_assert_eq!(mod_pow(base, exp, modulus), naive);
+        }
+
+        #[test]
+        fn test_mod_inverse_inverts(a in -1000i128..1000, modulus in 1i128..1000) {
+            match mod_inverse(a, modulus) {
+                Some(inverse) => prop_assert_eq!(mul_mod(a, inverse, modulus), 1 % modulus),
+                None => prop_assert_ne!(gcd(a, modulus), 1),
+            }
+        }
+
+        #[test]
+        fn test_crt_matches_search(congruences in prop::collection::vec((0i64..30, 1i64..12), 0..4)) {
+            let lcm = lcm_all(congruences.iter().map(|&(_, modulus)| modulus));
+            let search = (0..lcm).find(|x| congruences.iter().all(|&(r, m)| x % m == r % m));
+
+            prop_assert_eq!(crt(congruences.iter().copied()), search.map(|x| (x, lcm)));
+        }
+    }
+}
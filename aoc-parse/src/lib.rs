@@ -0,0 +1,326 @@
+//! nom parsers for the shapes puzzle inputs keep coming in. Lines may end in
+//! either `\n` or `\r\n`.
+
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use nom::{
+    branch::alt,
+    character::complete::{anychar, char, digit1, line_ending, space0, space1},
+    combinator::{all_consuming, map, map_opt, map_res, opt, recognize},
+    error::{Error, ErrorKind},
+    multi::{many0, separated_list1},
+    sequence::{delimited, pair, separated_pair, terminated, tuple},
+    IResult, Parser,
+};
+
+/// A cell of a [`Grid`] as `(row, col)`.
+pub type Position = (usize, usize);
+
+/// A number without a sign.
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// A number with an optional `-` or `+` in front.
+pub fn signed<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(
+        recognize(pair(opt(alt((char('-'), char('+')))), digit1)),
+        str::parse,
+    )(input)
+}
+
+/// Numbers split by spaces or tabs, like `7 6 4 2 1`.
+pub fn unsigned_list<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+    separated_list1(space1, unsigned)(input)
+}
+
+/// Numbers split by spaces or tabs, like `-3 0 12`.
+pub fn signed_list<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+    separated_list1(space1, signed)(input)
+}
+
+/// Items split by `separator`, which may have spaces either side of it.
+pub fn separated<'a, O, F>(
+    separator: char,
+    item: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(delimited(space0, char(separator), space0), item)
+}
+
+/// Two numbers joined by a dash, like `11-22`.
+pub fn range<T: FromStr>(input: &str) -> IResult<&str, RangeInclusive<T>> {
+    map(
+        separated_pair(unsigned, char('-'), unsigned),
+        |(start, end)| start..=end,
+    )(input)
+}
+
+/// A key then a colon and values split by spaces, like `190: 10 19`.
+pub fn key_values<'a, K, V, FK, FV>(
+    key: FK,
+    value: FV,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (K, Vec<V>)>
+where
+    FK: Parser<&'a str, K, Error<&'a str>>,
+    FV: Parser<&'a str, V, Error<&'a str>>,
+{
+    separated_pair(
+        key,
+        tuple((space0, char(':'), space0)),
+        separated_list1(space1, value),
+    )
+}
+
+/// One `line` after another, without the line ending after the last one.
+pub fn lines<'a, O, F>(line: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(line_ending, line)
+}
+
+/// The end of a line and an empty line after it.
+pub fn blank_line(input: &str) -> IResult<&str, ()> {
+    map(tuple((line_ending, space0, line_ending)), |_| ())(input)
+}
+
+/// Sections split by blank lines.
+pub fn sections<'a, O, F>(section: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    separated_list1(blank_line, section)
+}
+
+/// All of the input, which may end in line endings.
+pub fn whole<'a, O, F>(f: F) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    all_consuming(terminated(f, many0(line_ending)))
+}
+
+/// A single character cell, `None` from `f` meaning it's not one.
+pub fn cell<'a, T>(f: impl Fn(char) -> Option<T>) -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    map_opt(anychar, f)
+}
+
+/// Cells in rows of the same width, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len() / self.width
+    }
+
+    pub fn get(&self, (row, col): Position) -> Option<&T> {
+        (col < self.width)
+            .then(|| self.cells.get(row * self.width + col))
+            .flatten()
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn into_rows(self) -> Vec<Vec<T>> {
+        let (width, height) = (self.width, self.height());
+        let mut cells = self.cells.into_iter();
+
+        (0..height)
+            .map(|_| cells.by_ref().take(width).collect())
+            .collect()
+    }
+}
+
+/// A grid of `cell`s, see [`located_grid`].
+pub fn grid<'a, T, F>(cell: F) -> impl FnMut(&'a str) -> IResult<&'a str, Grid<T>>
+where
+    F: Parser<&'a str, T, Error<&'a str>>,
+{
+    map(located_grid(cell, |_| false), |(grid, _)| grid)
+}
+
+/// A grid of `cell`s along with the position of every cell `special` picks
+/// out, in row major order.
+///
+/// A row ends where `cell` doesn't match, the grid where a row is empty. All
+/// rows have to be as wide as the first one.
+pub fn located_grid<'a, T, F, P>(
+    mut cell: F,
+    mut special: P,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (Grid<T>, Vec<Position>)>
+where
+    F: Parser<&'a str, T, Error<&'a str>>,
+    P: FnMut(&T) -> bool,
+{
+    move |input: &'a str| {
+        let mut cells = Vec::new();
+        let mut located = Vec::new();
+        let mut width = None;
+        let mut row = 0;
+
+        // `start` is where the current row starts, `end` just past the last
+        // whole row
+        let (mut start, mut end) = (input, input);
+        loop {
+            let (mut rest, mut col) = (start, 0);
+            loop {
+                match cell.parse(rest) {
+                    Ok((next, _)) if next.len() == rest.len() => {
+                        return Err(nom::Err::Error(Error::new(rest, ErrorKind::Many1)));
+                    }
+                    Ok((next, item)) => {
+                        if special(&item) {
+                            located.push((row, col));
+                        }
+                        cells.push(item);
+                        rest = next;
+                        col += 1;
+                    }
+                    Err(nom::Err::Error(_)) => break,
+                    Err(err) => return Err(err),
+                }
+            }
+
+            if col == 0 {
+                break;
+            }
+            if *width.get_or_insert(col) != col {
+                return Err(nom::Err::Error(Error::new(start, ErrorKind::Verify)));
+            }
+
+            end = rest;
+            row += 1;
+            match line_ending::<_, Error<&str>>(rest) {
+                Ok((next, _)) => start = next,
+                Err(_) => break,
+            }
+        }
+
+        match width {
+            Some(width) => Ok((end, (Grid { width, cells }, located))),
+            None => Err(nom::Err::Error(Error::new(input, ErrorKind::Many1))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("42", 42)]
+    #[test_case("-42", -42)]
+    #[test_case("+7", 7)]
+    fn test_signed(input: &str, expected: i64) {
+        assert_eq!(signed::<i64>(input), Ok(("", expected)));
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(unsigned::<u8>("255 "), Ok((" ", 255)));
+        assert!(unsigned::<u8>("256").is_err());
+        assert!(unsigned::<u32>("-1").is_err());
+        assert_eq!(
+            unsigned_list::<u32>("7 6\t4  2"),
+            Ok(("", vec![7, 6, 4, 2]))
+        );
+        assert_eq!(signed_list::<i32>("-3 0 12"), Ok(("", vec![-3, 0, 12])));
+    }
+
+    #[test]
+    fn test_separated_ranges() {
+        let expected = vec![11..=22, 95..=115];
+
+        assert_eq!(
+            separated(',', range::<u64>)("11-22,95-115"),
+            Ok(("", expected.clone()))
+        );
+        assert_eq!(
+            separated(',', range::<u64>)("11-22 , 95-115\n"),
+            Ok(("\n", expected))
+        );
+    }
+
+    #[test_case("190: 10 19", (190, vec![10, 19]))]
+    #[test_case("3267:81 40 27", (3267, vec![81, 40, 27]))]
+    fn test_key_values(input: &str, expected: (u64, Vec<u64>)) {
+        assert_eq!(key_values(unsigned, unsigned)(input), Ok(("", expected)));
+    }
+
+    #[test_case("1 2\n3\n"; "trailing newline")]
+    #[test_case("1 2\r\n3\r\n"; "crlf")]
+    #[test_case("1 2\n3"; "no trailing newline")]
+    #[test_case("1 2\n3\n\n"; "trailing blank line")]
+    fn test_whole_lines(input: &str) {
+        assert_eq!(
+            whole(lines(unsigned_list::<u8>))(input),
+            Ok(("", vec![vec![1, 2], vec![3]]))
+        );
+    }
+
+    #[test_case("1|2\n3|4\n\n5,6\n7\n"; "lf")]
+    #[test_case("1|2\r\n3|4\r\n\r\n5,6\r\n7"; "crlf")]
+    fn test_sections(input: &str) {
+        let rules = lines(separated_pair(unsigned::<u8>, char('|'), unsigned));
+        let updates = lines(separated(',', unsigned::<u8>));
+        let (rest, (rules, updates)) = separated_pair(rules, blank_line, updates)(input).unwrap();
+
+        assert_eq!(rest.trim(), "");
+        assert_eq!(rules, [(1, 2), (3, 4)]);
+        assert_eq!(updates, [vec![5, 6], vec![7]]);
+
+        let (_, both) = whole(sections(lines(unsigned_list::<u8>)))("1\n2\n\n3").unwrap();
+        assert_eq!(both, [vec![vec![1], vec![2]], vec![vec![3]]]);
+    }
+
+    fn glyph(c: char) -> Option<char> {
+        ".#^".contains(c).then_some(c)
+    }
+
+    #[test_case("..#\n^..\n.^.\n"; "lf")]
+    #[test_case("..#\r\n^..\r\n.^."; "crlf")]
+    fn test_located_grid(input: &str) {
+        let (rest, (grid, located)) = located_grid(cell(glyph), |&c| c == '^')(input).unwrap();
+
+        assert_eq!(rest.trim(), "");
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(located, [(1, 0), (2, 1)]);
+        assert_eq!(grid.get((0, 2)), Some(&'#'));
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.rows().nth(1), Some(&['^', '.', '.'][..]));
+        assert_eq!(grid.into_rows()[2], ['.', '^', '.']);
+    }
+
+    #[test]
+    fn test_grid_stops_at_blank_line() {
+        let (rest, grid) = grid(cell(glyph))("..\n#.\n\n12").unwrap();
+
+        assert_eq!(rest, "\n\n12");
+        assert_eq!(grid.cells(), ['.', '.', '#', '.']);
+    }
+
+    #[test_case("..\n...\n"; "ragged")]
+    #[test_case("\n..\n"; "empty first row")]
+    fn test_bad_grid(input: &str) {
+        assert!(grid(cell(glyph))(input).is_err());
+    }
+}